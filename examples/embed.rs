@@ -0,0 +1,44 @@
+//! Embedding the checker in another program.
+//!
+//! ```bash
+//! GITHUB_TOKEN=... cargo run --example embed -- serde-rs/serde tokio-rs/tokio
+//! ```
+
+use anyhow::Result;
+use github_activity_check::{Config, GitHubClient, ProjectScorer, check_repository_with};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut builder = GitHubClient::builder().user_agent("embed-example/0.1");
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        builder = builder.token(token);
+    }
+    let client = builder.build()?;
+    let scorer = ProjectScorer::new();
+
+    // Stricter thresholds than the defaults, everything else stays default
+    let config = Config {
+        max_days: Some(30),
+        min_contributors: Some(5),
+        ..Default::default()
+    };
+
+    for spec in std::env::args().skip(1) {
+        let Some((owner, repo)) = spec.split_once('/') else {
+            eprintln!("Skipping '{spec}', expected owner/repo");
+            continue;
+        };
+
+        match check_repository_with(&client, &scorer, owner, repo, &config).await {
+            Ok(report) => println!(
+                "{owner}/{repo}: {} (score {:.2}, last commit {})",
+                if report.project_alive { "alive" } else { "likely dead" },
+                report.score.total,
+                report.last_commit.date_utc.date_naive()
+            ),
+            Err(e) => eprintln!("{owner}/{repo}: {e:#}"),
+        }
+    }
+
+    Ok(())
+}
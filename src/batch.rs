@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use futures::{StreamExt, stream};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config::{Config, HostConfig};
+use crate::forge::{FetchOptions, ForgeClient, RepoTarget};
+use crate::http::DEFAULT_CONCURRENCY;
+use crate::output::RepositoryReport;
+use crate::scoring::ProjectScorer;
+
+/// A repository listed in a batch file
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    pub target: RepoTarget,
+    /// Path of the file declaring the repository
+    pub source: String,
+    /// 1-based line number of the declaration
+    pub line: usize,
+}
+
+/// Outcome of checking one batch entry
+pub struct BatchResult {
+    pub entry: BatchEntry,
+    pub report: Result<RepositoryReport>,
+}
+
+/// Loads a batch file with one repository spec (`owner/repo`, URL, ...) per line,
+/// see [`RepoTarget::parse`]. Empty lines and lines starting with `#` are ignored.
+pub fn load_batch_file<P: AsRef<Path>>(path: P, hosts: &BTreeMap<String, HostConfig>) -> Result<Vec<BatchEntry>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read batch file: {}", path.display()))?;
+    parse_batch(&content, &path.display().to_string(), hosts)
+}
+
+/// Checks every entry with the client `forge_for` returns for its target.
+/// Entries sharing a client are fetched in chunks of [`ForgeClient::batch_size`],
+/// so forges that can query several repositories at once need fewer requests,
+/// and up to `config.concurrency` chunks are fetched at the same time.
+/// `on_result` is called as soon as a repository has been checked; a failing
+/// repository does not stop the run. The returned results are in entry order.
+pub async fn check_batch<'a, F: ForgeClient + 'a>(
+    forge_for: impl Fn(&RepoTarget) -> Result<&'a F>,
+    scorer: &ProjectScorer,
+    config: &Config,
+    entries: Vec<BatchEntry>,
+    mut on_result: impl FnMut(&BatchResult) -> Result<()>,
+) -> Result<Vec<BatchResult>> {
+    let mut results: Vec<Option<BatchResult>> = entries.iter().map(|_| None).collect();
+    let mut entries: Vec<Option<BatchEntry>> = entries.into_iter().map(Some).collect();
+
+    // Entry indexes grouped by client, in order of first appearance
+    let mut groups: Vec<(&F, Vec<usize>)> = Vec::new();
+    for (index, entry) in entries.iter_mut().enumerate() {
+        let target = &entry.as_ref().expect("entry not taken yet").target;
+        match forge_for(target) {
+            Ok(forge) => match groups.iter_mut().find(|(f, _)| std::ptr::eq(*f, forge)) {
+                Some((_, indexes)) => indexes.push(index),
+                None => groups.push((forge, vec![index])),
+            },
+            Err(e) => {
+                let result = BatchResult { entry: entry.take().expect("entry not taken yet"), report: Err(e) };
+                on_result(&result)?;
+                results[index] = Some(result);
+            }
+        }
+    }
+
+    let jobs: Vec<_> = groups
+        .iter()
+        .flat_map(|(forge, indexes)| {
+            indexes.chunks(forge.batch_size().max(1)).map(|chunk| {
+                let repos: Vec<(String, String)> = chunk
+                    .iter()
+                    .map(|&i| {
+                        let target = &entries[i].as_ref().expect("entry not taken yet").target;
+                        (target.owner.clone(), target.repo.clone())
+                    })
+                    .collect();
+                (*forge, chunk.to_vec(), repos)
+            })
+        })
+        .collect();
+
+    // Up to `concurrency` chunks are fetched at a time, results arrive as they complete
+    let options = &FetchOptions::for_config(config, scorer.now());
+    let concurrency = config.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1);
+    let mut fetches = stream::iter(jobs)
+        .map(|(forge, chunk, repos)| async move {
+            let repos: Vec<(&str, &str)> = repos.iter().map(|(owner, repo)| (owner.as_str(), repo.as_str())).collect();
+            crate::verbose_println!(config, "Fetching {} repositories from {} API...", repos.len(), forge.name());
+            (chunk, forge.fetch_repositories(&repos, options).await)
+        })
+        .buffer_unordered(concurrency);
+
+    while let Some((chunk, fetched)) = fetches.next().await {
+        for (index, data) in chunk.into_iter().zip(fetched) {
+            let entry = entries[index].take().expect("entry fetched once");
+            let report = data.map(|data| {
+                crate::build_report(scorer, &entry.target.owner, &entry.target.repo, config, &data)
+            });
+            let result = BatchResult { entry, report };
+            on_result(&result)?;
+            results[index] = Some(result);
+        }
+    }
+
+    Ok(results.into_iter().map(|r| r.expect("every entry checked")).collect())
+}
+
+fn parse_batch(content: &str, source: &str, hosts: &BTreeMap<String, HostConfig>) -> Result<Vec<BatchEntry>> {
+    let mut entries = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        // Strip trailing comments
+        let spec = line.split('#').next().unwrap_or("").trim();
+        if spec.is_empty() {
+            continue;
+        }
+
+        let target = RepoTarget::parse(spec, hosts).with_context(|| format!("{source}:{line_no}"))?;
+
+        entries.push(BatchEntry {
+            target,
+            source: source.to_string(),
+            line: line_no,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::{FakeForge, ForgeKind};
+
+    #[test]
+    fn test_parse_batch() {
+        let content = "# dependencies\nrust-lang/rust\n\n  serde-rs/serde  # serialization\n";
+        let entries = parse_batch(content, "repos.txt", &BTreeMap::new()).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].target, RepoTarget::github("rust-lang", "rust"));
+        assert_eq!(entries[0].line, 2);
+        assert_eq!(entries[1].target, RepoTarget::github("serde-rs", "serde"));
+        assert_eq!(entries[1].line, 4);
+        assert_eq!(entries[1].source, "repos.txt");
+    }
+
+    #[test]
+    fn test_parse_batch_specs() {
+        let content = "https://github.com/tokio-rs/tokio/tree/master\ngit@gitlab.com:gitlab-org/cli.git\n";
+        let entries = parse_batch(content, "repos.txt", &BTreeMap::new()).unwrap();
+
+        assert_eq!(entries[0].target, RepoTarget::github("tokio-rs", "tokio"));
+        assert_eq!(entries[1].target.forge, ForgeKind::GitLab);
+        assert_eq!(entries[1].target.to_string(), "gitlab:gitlab-org/cli");
+    }
+
+    #[tokio::test]
+    async fn test_check_batch_chunks_per_client() {
+        let github = FakeForge { batch_size: 2, ..Default::default() };
+        let gitlab = FakeForge::default();
+        let content = "a/one\ngitlab:g/two\na/missing\na/three\n";
+        let entries = parse_batch(content, "repos.txt", &BTreeMap::new()).unwrap();
+
+        let mut streamed = Vec::new();
+        let results = check_batch(
+            |target: &RepoTarget| Ok(if target.forge == ForgeKind::GitLab { &gitlab } else { &github }),
+            &ProjectScorer::new(),
+            // One chunk at a time keeps the streaming order predictable
+            &Config { concurrency: Some(1), ..Default::default() },
+            entries,
+            |result| {
+                streamed.push(result.entry.line);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*github.fetches.lock().unwrap(), vec![2, 1]);
+        assert_eq!(*gitlab.fetches.lock().unwrap(), vec![1]);
+        assert_eq!(streamed, vec![1, 3, 4, 2]);
+        let lines: Vec<usize> = results.iter().map(|r| r.entry.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4]);
+        assert!(results[2].report.is_err());
+        assert_eq!(results[3].report.as_ref().unwrap().repo, "three");
+    }
+
+    #[test]
+    fn test_parse_batch_invalid_line() {
+        let err = parse_batch("rust-lang/rust\nnot-a-repo\n", "repos.txt", &BTreeMap::new()).unwrap_err();
+        assert!(err.to_string().contains("repos.txt:2"));
+        assert!(parse_batch("a/b/c\n", "repos.txt", &BTreeMap::new()).is_err());
+    }
+}
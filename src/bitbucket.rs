@@ -0,0 +1,458 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{Client, Url, header};
+use serde::Deserialize;
+
+use crate::forge::{ForgeClient, Unsupported};
+use crate::http::{Cassette, DEFAULT_CONCURRENCY, HttpResponse, ResponseCache, Transport};
+use crate::types::{AuthorMeta, CommitInfo, CommitMeta, ReleaseInfo};
+
+static DEFAULT_CLOUD_BASE_URL: &str = "https://api.bitbucket.org/2.0";
+static DEFAULT_USER_AGENT: &str = "github-activity-check/0.1";
+
+/// Page size used when Bitbucket Server has to be paged through to count items
+const SERVER_PAGE_SIZE: usize = 1000;
+
+/// Builder for [`BitbucketCloudClient`] and [`BitbucketServerClient`]
+#[derive(Default)]
+pub struct BitbucketClientBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    concurrency: Option<usize>,
+    cache: Option<ResponseCache>,
+    cassette: Option<Cassette>,
+}
+
+impl BitbucketClientBuilder {
+    /// Access token sent as `Authorization: Bearer`, or `username:app_password` for basic auth
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// API root, `https://api.bitbucket.org/2.0` for Cloud or `https://host/rest/api/1.0` for Server
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Maximum number of requests in flight (default: 4)
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Caches responses on disk and revalidates them with conditional requests
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Records every exchange into the cassette, or serves them from it
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    fn build_http(&self) -> Result<Http> {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::USER_AGENT, header::HeaderValue::from_static(DEFAULT_USER_AGENT));
+        headers.insert(header::ACCEPT, header::HeaderValue::from_static("application/json"));
+        let mut basic_auth = None;
+        match self.token.as_deref().map(|t| t.split_once(':').ok_or(t)) {
+            Some(Ok((username, password))) => basic_auth = Some((username.to_string(), password.to_string())),
+            Some(Err(token)) => {
+                headers.insert(header::AUTHORIZATION, header::HeaderValue::from_str(&format!("Bearer {token}"))?);
+            }
+            None => {}
+        }
+        let client = Client::builder().default_headers(headers).build()?;
+        let transport = Transport::new(client, self.concurrency.unwrap_or(DEFAULT_CONCURRENCY))
+            .with_cache(self.cache.clone(), self.token.as_deref())
+            .with_cassette(self.cassette.clone());
+        Ok(Http { transport, basic_auth })
+    }
+
+    pub fn build(self) -> Result<BitbucketCloudClient> {
+        let http = self.build_http()?;
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_CLOUD_BASE_URL).trim_end_matches('/').to_string();
+        Ok(BitbucketCloudClient { http, base_url })
+    }
+
+    pub fn build_server(self) -> Result<BitbucketServerClient> {
+        let http = self.build_http()?;
+        let base_url = self
+            .base_url
+            .as_deref()
+            .context("Bitbucket Server needs an API root, e.g. https://bitbucket.example.com/rest/api/1.0")?
+            .trim_end_matches('/')
+            .to_string();
+        Ok(BitbucketServerClient { http, base_url })
+    }
+}
+
+/// HTTP client shared by both APIs, app passwords need basic auth on every request
+struct Http {
+    transport: Transport,
+    basic_auth: Option<(String, String)>,
+}
+
+impl Http {
+    async fn get(&self, url: &str, owner: &str, repo: &str) -> Result<HttpResponse> {
+        let mut request = self.transport.get(url);
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+        self.transport.send(request).await
+            .with_context(|| format!("Failed to fetch data from {url}"))?
+            .error_for_status()
+            .with_context(|| format!("Bitbucket API error for repository {owner}/{repo}"))
+    }
+}
+
+/// Splits a raw git author (`Name <email>`) into name and email
+fn parse_raw_author(raw: &str) -> (String, String) {
+    match (raw.find('<'), raw.rfind('>')) {
+        (Some(start), Some(end)) if start < end => {
+            (raw[..start].trim().to_string(), raw[start + 1..end].trim().to_string())
+        }
+        _ => (raw.trim().to_string(), String::new()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Bitbucket Cloud (2.0 API)
+// ---------------------------------------------------------------------------
+
+/// Client for the Bitbucket Cloud 2.0 API. Commit and contributor totals are
+/// not available there; issues only when the repository's tracker is enabled.
+pub struct BitbucketCloudClient {
+    http: Http,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct CloudRepository {
+    has_issues: bool,
+    mainbranch: Option<CloudBranch>,
+}
+
+#[derive(Deserialize)]
+struct CloudBranch {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct CloudPage<T> {
+    size: Option<usize>,
+    #[serde(default = "Vec::new")]
+    values: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct CloudCommit {
+    hash: String,
+    date: DateTime<Utc>,
+    message: String,
+    author: CloudAuthor,
+}
+
+#[derive(Deserialize)]
+struct CloudAuthor {
+    raw: String,
+}
+
+#[derive(Deserialize)]
+struct CloudTag {
+    name: String,
+    target: CloudTagTarget,
+}
+
+#[derive(Deserialize)]
+struct CloudTagTarget {
+    date: Option<DateTime<Utc>>,
+}
+
+impl BitbucketCloudClient {
+    pub fn builder() -> BitbucketClientBuilder {
+        BitbucketClientBuilder::default()
+    }
+
+    fn repo_url(&self, owner: &str, repo: &str) -> String {
+        format!("{}/repositories/{owner}/{repo}", self.base_url)
+    }
+
+    async fn get_repository(&self, owner: &str, repo: &str) -> Result<CloudRepository> {
+        self.http.get(&self.repo_url(owner, repo), owner, repo).await?.json()
+            .context("Failed to parse repository response")
+    }
+
+    async fn fetch_size(&self, url: &str, owner: &str, repo: &str) -> Result<usize> {
+        let page: CloudPage<serde_json::Value> = self.http.get(url, owner, repo).await?.json()
+            .context("Failed to parse paged response")?;
+        page.size.with_context(|| format!("Bitbucket did not report a size for {url}"))
+    }
+
+    fn unsupported(metric: &'static str) -> anyhow::Error {
+        Unsupported { forge: "Bitbucket Cloud", metric }.into()
+    }
+}
+
+impl ForgeClient for BitbucketCloudClient {
+    fn name(&self) -> &'static str {
+        "Bitbucket Cloud"
+    }
+
+    async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
+        // Without a branch the listing covers all branches, restrict it to the main branch
+        let repository = self.get_repository(owner, repo).await?;
+        let branch = repository
+            .mainbranch
+            .with_context(|| format!("Repository {owner}/{repo} has no commits"))?;
+        let url = format!("{}/commits/{}?pagelen=1", self.repo_url(owner, repo), branch.name);
+        let page: CloudPage<CloudCommit> = self.http.get(&url, owner, repo).await?.json()
+            .context("Failed to parse commit response as JSON")?;
+        let commit = page
+            .values
+            .into_iter()
+            .next()
+            .with_context(|| format!("Repository {owner}/{repo} has no commits"))?;
+        let (name, email) = parse_raw_author(&commit.author.raw);
+        Ok(CommitInfo {
+            sha: commit.hash,
+            commit: CommitMeta {
+                author: AuthorMeta { name, email, date: commit.date },
+                message: commit.message,
+            },
+        })
+    }
+
+    async fn get_commit_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        Err(Self::unsupported("Commit count"))
+    }
+
+    async fn get_contributors_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        Err(Self::unsupported("Contributors"))
+    }
+
+    async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        let url = format!("{}/pullrequests?state=OPEN&pagelen=1", self.repo_url(owner, repo));
+        self.fetch_size(&url, owner, repo).await
+    }
+
+    async fn get_open_issues_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        if !self.get_repository(owner, repo).await?.has_issues {
+            return Err(Self::unsupported("Issue tracker"));
+        }
+        let url = Url::parse_with_params(
+            &format!("{}/issues", self.repo_url(owner, repo)),
+            &[
+                ("q", r#"state="new" OR state="open" OR state="on hold""#),
+                ("pagelen", "1"),
+            ],
+        )?;
+        self.fetch_size(url.as_str(), owner, repo).await
+    }
+
+    /// Tags stand in for releases, newest first
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
+        let url = format!("{}/refs/tags?sort=-target.date&pagelen=1", self.repo_url(owner, repo));
+        let page: CloudPage<CloudTag> = self.http.get(&url, owner, repo).await?.json()
+            .context("Failed to parse tags response")?;
+        Ok(page.values.into_iter().next().map(|tag| ReleaseInfo {
+            tag_name: tag.name,
+            name: None,
+            published_at: tag.target.date,
+            prerelease: false,
+            draft: false,
+        }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Bitbucket Server / Data Center (1.0 REST API)
+// ---------------------------------------------------------------------------
+
+/// Client for the Bitbucket Server / Data Center 1.0 REST API. The owner is the
+/// project key (or `~user` for personal repositories). Server has no issue tracker.
+pub struct BitbucketServerClient {
+    http: Http,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerPage<T> {
+    #[serde(default = "Vec::new")]
+    values: Vec<T>,
+    #[serde(default)]
+    is_last_page: bool,
+    next_page_start: Option<usize>,
+    total_count: Option<usize>,
+    author_count: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerCommit {
+    id: String,
+    author: ServerAuthor,
+    author_timestamp: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerAuthor {
+    name: String,
+    email_address: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerTag {
+    display_id: String,
+    latest_commit: String,
+}
+
+impl BitbucketServerClient {
+    pub fn builder() -> BitbucketClientBuilder {
+        BitbucketClientBuilder::default()
+    }
+
+    fn repo_url(&self, owner: &str, repo: &str) -> String {
+        format!("{}/projects/{owner}/repos/{repo}", self.base_url)
+    }
+
+    async fn get_commit_page(&self, owner: &str, repo: &str) -> Result<ServerPage<ServerCommit>> {
+        let url = format!("{}/commits?limit=1&withCounts=true", self.repo_url(owner, repo));
+        self.http.get(&url, owner, repo).await?.json()
+            .context("Failed to parse commit response as JSON")
+    }
+
+    async fn get_commit(&self, owner: &str, repo: &str, id: &str) -> Result<ServerCommit> {
+        let url = format!("{}/commits/{id}", self.repo_url(owner, repo));
+        self.http.get(&url, owner, repo).await?.json()
+            .context("Failed to parse commit response as JSON")
+    }
+}
+
+fn server_commit_info(commit: ServerCommit) -> Result<CommitInfo> {
+    let date = Utc
+        .timestamp_millis_opt(commit.author_timestamp)
+        .single()
+        .context("Invalid commit timestamp")?;
+    Ok(CommitInfo {
+        sha: commit.id,
+        commit: CommitMeta {
+            author: AuthorMeta {
+                name: commit.author.name,
+                email: commit.author.email_address.unwrap_or_default(),
+                date,
+            },
+            message: commit.message,
+        },
+    })
+}
+
+impl ForgeClient for BitbucketServerClient {
+    fn name(&self) -> &'static str {
+        "Bitbucket Server"
+    }
+
+    async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
+        let page = self.get_commit_page(owner, repo).await?;
+        let commit = page
+            .values
+            .into_iter()
+            .next()
+            .with_context(|| format!("Repository {owner}/{repo} has no commits"))?;
+        server_commit_info(commit)
+    }
+
+    async fn get_commit_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        self.get_commit_page(owner, repo)
+            .await?
+            .total_count
+            .with_context(|| format!("Bitbucket Server did not report a commit count for {owner}/{repo}"))
+    }
+
+    /// Distinct commit authors as counted by the server
+    async fn get_contributors_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        self.get_commit_page(owner, repo)
+            .await?
+            .author_count
+            .with_context(|| format!("Bitbucket Server did not report an author count for {owner}/{repo}"))
+    }
+
+    async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        // Paged responses carry no totals, count the pages
+        let mut count = 0;
+        let mut start = 0;
+        loop {
+            let url = format!(
+                "{}/pull-requests?state=OPEN&limit={SERVER_PAGE_SIZE}&start={start}",
+                self.repo_url(owner, repo)
+            );
+            let page: ServerPage<serde_json::Value> = self.http.get(&url, owner, repo).await?.json()
+                .context("Failed to parse pull requests response")?;
+            count += page.values.len();
+            match page.next_page_start {
+                Some(next) if !page.is_last_page => start = next,
+                _ => return Ok(count),
+            }
+        }
+    }
+
+    async fn get_open_issues_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        Err(Unsupported { forge: "Bitbucket Server", metric: "Issues" }.into())
+    }
+
+    /// The most recently created tag stands in for a release
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
+        let url = format!("{}/tags?orderBy=MODIFICATION&limit=1", self.repo_url(owner, repo));
+        let page: ServerPage<ServerTag> = self.http.get(&url, owner, repo).await?.json()
+            .context("Failed to parse tags response")?;
+        let Some(tag) = page.values.into_iter().next() else {
+            return Ok(None);
+        };
+        let commit = server_commit_info(self.get_commit(owner, repo, &tag.latest_commit).await?)?;
+        Ok(Some(ReleaseInfo {
+            tag_name: tag.display_id,
+            name: None,
+            published_at: Some(commit.commit.author.date),
+            prerelease: false,
+            draft: false,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_raw_author() {
+        assert_eq!(
+            parse_raw_author("Jane Doe <jane@example.com>"),
+            ("Jane Doe".to_string(), "jane@example.com".to_string())
+        );
+        assert_eq!(parse_raw_author("jane"), ("jane".to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_server_commit_timestamp() {
+        let page: ServerPage<ServerCommit> = serde_json::from_str(
+            r#"{"values":[{"id":"abc","author":{"name":"Jane","emailAddress":"jane@example.com"},
+                "authorTimestamp":1700000000000,"message":"Fix"}],
+                "isLastPage":false,"nextPageStart":1,"totalCount":1234,"authorCount":12}"#,
+        )
+        .unwrap();
+        assert_eq!(page.total_count, Some(1234));
+        assert_eq!(page.author_count, Some(12));
+
+        let info = server_commit_info(page.values.into_iter().next().unwrap()).unwrap();
+        assert_eq!(info.commit.author.date.to_rfc3339(), "2023-11-14T22:13:20+00:00");
+        assert_eq!(info.commit.author.email, "jane@example.com");
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Source of the current time for scoring. Day-based values (recency,
+/// release age, "N days ago") are computed relative to it, so a fixed clock
+/// gives reproducible scores.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at the given instant, e.g. for `--as-of`
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Parses an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC)
+pub fn parse_instant(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(instant) = DateTime::parse_from_rfc3339(s) {
+        return Ok(instant.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{s}', expected YYYY-MM-DD or an RFC 3339 timestamp"))?;
+    Ok(date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instant() {
+        assert_eq!(parse_instant("2024-05-01").unwrap().to_rfc3339(), "2024-05-01T00:00:00+00:00");
+        assert_eq!(
+            parse_instant("2024-05-01T14:30:00+02:00").unwrap().to_rfc3339(),
+            "2024-05-01T12:30:00+00:00"
+        );
+        assert!(parse_instant("May 1st").is_err());
+        assert!(parse_instant("2024-13-01").is_err());
+    }
+}
@@ -1,23 +1,31 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
+use crate::clock;
+use crate::forge::{ForgeKind, RepoTarget};
+use crate::http::{Cassette, DEFAULT_CONCURRENCY, DEFAULT_TTL, ResponseCache};
 use crate::output::OutputFormat;
+use crate::types::{ACTIVITY_DAYS, CI_RUNS_MAX};
 
+/// Command line arguments merged with the optional TOML configuration file
 #[derive(Parser, Deserialize, Serialize, Debug, Clone, Default)]
 #[command(name = "github-activity-check")]
 #[command(about = "CLI tool to check if GitHub repositories are actively maintained")]
 #[command(version)]
 pub struct Config {
-    /// Repository owner
+    /// Repository owner, or a single repository spec: owner/repo, gitlab:group/project, URL, ...
     #[arg(value_name = "OWNER")]
     #[serde(skip)]
     pub owner: Option<String>,
     
-    /// Repository name
+    /// Repository name (omit when OWNER is a repository spec)
     #[arg(value_name = "REPO")]
     #[serde(skip)]
     pub repo: Option<String>,
@@ -27,7 +35,8 @@ pub struct Config {
     #[serde(skip)]
     pub config_file: Option<String>,
     
-    /// Output format
+    /// Output format: default, json, github-actions, sarif, junit, ndjson, or field:name
+    /// (github-actions is selected automatically when GITHUB_ACTIONS=true)
     #[arg(long, value_parser = OutputFormat::from_str)]
     #[serde(default)]
     pub format: Option<OutputFormat>,
@@ -62,6 +71,114 @@ pub struct Config {
     #[serde(default)]
     pub max_release_days: Option<i64>,
     
+    /// Weeks with commits in the last year for a full cadence score
+    #[arg(long, value_name = "WEEKS")]
+    #[serde(default)]
+    pub min_active_weeks: Option<usize>,
+    
+    /// Stable releases in the last year for a full release cadence score (default: 4)
+    #[arg(long, value_name = "N")]
+    #[serde(default)]
+    pub min_releases: Option<usize>,
+    
+    /// Days of pull requests the responsiveness metrics cover, at most 365 (default: 90)
+    #[arg(long, value_name = "DAYS")]
+    #[serde(default)]
+    pub pr_window_days: Option<i64>,
+    
+    /// Days after which an open pull request without a maintainer response is stale (default: 30)
+    #[arg(long, value_name = "DAYS")]
+    #[serde(default)]
+    pub stale_pr_days: Option<i64>,
+    
+    /// Latest finished CI runs of the default branch the pass rate covers, at most 100 (default: 20)
+    #[arg(long, value_name = "N")]
+    #[serde(default)]
+    pub ci_runs: Option<usize>,
+    
+    /// Fetch the CI results of the default branch for the `ci` section
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub ci: bool,
+    
+    /// Include the CI results of the default branch in the score (fetches them too)
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub score_ci: bool,
+    
+    /// Fetch the pull requests and issues of the last year with their comments
+    /// for the `pull_requests` and `issues` sections (several requests per repository)
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub triage: bool,
+    
+    /// Read the dependents count from the forge's web pages, without credentials
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub dependents: bool,
+    
+    /// SPDX identifiers of the only allowed licenses, `NONE` for no license;
+    /// any other license fails the check
+    #[arg(long, value_name = "SPDX", value_delimiter = ',')]
+    #[serde(default)]
+    pub license_allow: Option<Vec<String>>,
+    
+    /// SPDX identifiers of licenses that fail the check, `NONE` for no license
+    #[arg(long, value_name = "SPDX", value_delimiter = ',')]
+    #[serde(default)]
+    pub license_deny: Option<Vec<String>>,
+    
+    /// Maximum number of API requests in flight per forge host, also bounds
+    /// how many repositories of a batch are checked at the same time (default: 4)
+    #[arg(long, value_name = "N")]
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    
+    /// Directory of the HTTP response cache (default: ~/.cache/github-activity-check)
+    #[arg(long, value_name = "DIR")]
+    #[serde(default)]
+    pub cache_dir: Option<String>,
+    
+    /// Seconds a cached response is used without revalidation (default: 600)
+    #[arg(long, value_name = "SECONDS")]
+    #[serde(default)]
+    pub cache_ttl: Option<u64>,
+    
+    /// Don't read or write the HTTP response cache
+    #[arg(long, default_value_t = false)]
+    #[serde(skip)]
+    pub no_cache: bool,
+    
+    /// Serve API responses only from the cache, without network access
+    #[arg(long, default_value_t = false)]
+    #[serde(skip)]
+    pub offline: bool,
+    
+    /// Save every API exchange as a cassette file in DIR
+    #[arg(long, value_name = "DIR")]
+    #[serde(skip)]
+    pub record: Option<String>,
+    
+    /// Serve API responses from cassettes recorded with --record, without network access
+    #[arg(long, value_name = "DIR")]
+    #[serde(skip)]
+    pub replay: Option<String>,
+    
+    /// Batch file with one repository spec per line (checks every listed repository)
+    #[arg(long, value_name = "FILE")]
+    #[serde(skip)]
+    pub batch: Option<String>,
+    
+    /// Analyze a local git repository instead of querying a forge API
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip)]
+    pub local: Option<String>,
+    
+    /// Score as of DATE (YYYY-MM-DD or RFC 3339) instead of now
+    #[arg(long, value_name = "DATE", value_parser = clock::parse_instant)]
+    #[serde(skip)]
+    pub as_of: Option<DateTime<Utc>>,
+    
     /// History file path for storing last run data
     #[arg(long)]
     #[serde(skip)]
@@ -76,6 +193,32 @@ pub struct Config {
     #[arg(long, default_value_t = false)]
     #[serde(skip)]
     pub verbose: bool,
+
+    /// Per-host forge settings, keyed by host name (config file only)
+    #[arg(skip)]
+    #[serde(default)]
+    pub hosts: BTreeMap<String, HostConfig>,
+}
+
+/// Forge settings for one host, e.g. `[hosts."codeberg.org"]`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct HostConfig {
+    /// Forge software running on the host, required for hosts that can't be recognized by name
+    pub forge: Option<ForgeKind>,
+    /// API root, e.g. `https://git.example.com/api/v1`
+    pub api_url: Option<String>,
+    /// Access token for the host
+    pub token: Option<String>,
+    /// Environment variable holding the access token (used when `token` is not set)
+    pub token_env: Option<String>,
+}
+
+impl HostConfig {
+    pub fn get_token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| self.token_env.as_ref().and_then(|name| std::env::var(name).ok()))
+    }
 }
 
 impl Config {
@@ -97,17 +240,40 @@ impl Config {
         self.max_days = self.max_days.or(file_config.max_days);
         self.prs_scale = self.prs_scale.or(file_config.prs_scale);
         self.issues_scale = self.issues_scale.or(file_config.issues_scale);
+        self.min_active_weeks = self.min_active_weeks.or(file_config.min_active_weeks);
+        self.min_releases = self.min_releases.or(file_config.min_releases);
+        self.pr_window_days = self.pr_window_days.or(file_config.pr_window_days);
+        self.stale_pr_days = self.stale_pr_days.or(file_config.stale_pr_days);
+        self.ci_runs = self.ci_runs.or(file_config.ci_runs);
+        self.ci = self.ci || file_config.ci;
+        self.score_ci = self.score_ci || file_config.score_ci;
+        self.triage = self.triage || file_config.triage;
+        self.dependents = self.dependents || file_config.dependents;
+        self.license_allow = self.license_allow.or(file_config.license_allow);
+        self.license_deny = self.license_deny.or(file_config.license_deny);
+        self.concurrency = self.concurrency.or(file_config.concurrency);
+        self.cache_dir = self.cache_dir.or(file_config.cache_dir);
+        self.cache_ttl = self.cache_ttl.or(file_config.cache_ttl);
+        if self.hosts.is_empty() {
+            self.hosts = file_config.hosts;
+        }
         self
     }
 
     pub fn with_defaults(mut self) -> Self {
-        self.format = self.format.or(Some(OutputFormat::Default));
+        self.format = self.format.or_else(|| Some(OutputFormat::detect()));
         self.min_commits = self.min_commits.or(Some(100));
         self.min_contributors = self.min_contributors.or(Some(3));
         self.max_days = self.max_days.or(Some(60));
         self.prs_scale = self.prs_scale.or(Some(10.0));
         self.issues_scale = self.issues_scale.or(Some(20.0));
         self.max_release_days = self.max_release_days.or(Some(365)); // 1 year default
+        self.min_active_weeks = self.min_active_weeks.or(Some(26)); // every other week
+        self.min_releases = self.min_releases.or(Some(4)); // quarterly
+        self.pr_window_days = self.pr_window_days.or(Some(90));
+        self.stale_pr_days = self.stale_pr_days.or(Some(30));
+        self.ci_runs = self.ci_runs.or(Some(20));
+        self.concurrency = self.concurrency.or(Some(DEFAULT_CONCURRENCY));
         self
     }
 
@@ -120,6 +286,11 @@ impl Config {
         self.repo.as_ref().expect("Repo should be set")
     }
 
+    /// Repository selected by the positional arguments
+    pub fn target(&self) -> Result<RepoTarget> {
+        RepoTarget::from_args(self.get_owner(), self.repo.as_deref(), &self.hosts)
+    }
+
     pub fn get_format(&self) -> &OutputFormat {
         self.format.as_ref().expect("Format should be set")
     }
@@ -148,13 +319,109 @@ impl Config {
         self.max_release_days.expect("max_release_days should be set")
     }
 
+    pub fn get_min_active_weeks(&self) -> usize {
+        self.min_active_weeks.expect("min_active_weeks should be set")
+    }
+
+    pub fn get_min_releases(&self) -> usize {
+        self.min_releases.expect("min_releases should be set")
+    }
+
+    pub fn get_pr_window_days(&self) -> i64 {
+        self.pr_window_days.expect("pr_window_days should be set")
+    }
+
+    pub fn get_stale_pr_days(&self) -> i64 {
+        self.stale_pr_days.expect("stale_pr_days should be set")
+    }
+
+    pub fn get_ci_runs(&self) -> usize {
+        self.ci_runs.expect("ci_runs should be set")
+    }
+
+    /// Whether an allow or deny list is set
+    pub fn has_license_policy(&self) -> bool {
+        self.license_allow.is_some() || self.license_deny.is_some()
+    }
+
+    /// Whether the license policy allows the license with `spdx_id`; identifiers
+    /// are compared case-insensitively and the deny list wins
+    pub fn license_allowed(&self, spdx_id: &str) -> bool {
+        let listed = |list: &Option<Vec<String>>| {
+            list.as_ref().map(|ids| ids.iter().any(|id| id.eq_ignore_ascii_case(spdx_id)))
+        };
+        listed(&self.license_allow).unwrap_or(true) && !listed(&self.license_deny).unwrap_or(false)
+    }
+
+    pub fn get_concurrency(&self) -> usize {
+        self.concurrency.expect("concurrency should be set")
+    }
+
+    /// Response cache selected by the cache options, `None` with `--no-cache`
+    pub fn response_cache(&self) -> Option<ResponseCache> {
+        if self.no_cache {
+            return None;
+        }
+        let dir = self.cache_dir.as_ref().map(PathBuf::from).or_else(ResponseCache::default_dir)?;
+        let ttl = self.cache_ttl.map_or(DEFAULT_TTL, Duration::from_secs);
+        Some(ResponseCache::new(dir).ttl(ttl).offline(self.offline))
+    }
+
+    /// Cassette selected by `--record` or `--replay`
+    pub fn cassette(&self) -> Result<Option<Cassette>> {
+        match (&self.record, &self.replay) {
+            (Some(dir), _) => Ok(Some(Cassette::record(dir))),
+            (None, Some(dir)) => Cassette::replay(dir).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
     pub fn validate(&self) -> Result<()> {
+        if self.record.is_some() && self.replay.is_some() {
+            anyhow::bail!("--record and --replay cannot be combined");
+        }
+        if self.concurrency == Some(0) {
+            anyhow::bail!("--concurrency must be at least 1");
+        }
+        if self.min_releases == Some(0) {
+            anyhow::bail!("--min-releases must be at least 1");
+        }
+        // Forges fetch a year of pull requests
+        if self.pr_window_days.is_some_and(|days| !(1..=ACTIVITY_DAYS).contains(&days)) {
+            anyhow::bail!("--pr-window-days must be between 1 and {ACTIVITY_DAYS}");
+        }
+        // Forges keep the latest 100 runs
+        if self.ci_runs.is_some_and(|runs| !(1..=CI_RUNS_MAX).contains(&runs)) {
+            anyhow::bail!("--ci-runs must be between 1 and {CI_RUNS_MAX}");
+        }
+        if self.offline && self.no_cache {
+            anyhow::bail!("--offline needs the cache, it cannot be combined with --no-cache");
+        }
+        if self.offline && self.response_cache().is_none() {
+            anyhow::bail!("--offline needs a cache directory, set --cache-dir");
+        }
+        if self.batch.is_some() {
+            if self.local.is_some() {
+                anyhow::bail!("--local cannot be combined with --batch");
+            }
+            if self.owner.is_some() || self.repo.is_some() {
+                anyhow::bail!("OWNER and REPO cannot be combined with --batch");
+            }
+            if self.history.is_some() || self.check.is_some() {
+                anyhow::bail!("--history and --check are not supported with --batch");
+            }
+            return Ok(());
+        }
+        if self.local.is_some() {
+            if self.owner.is_some() || self.repo.is_some() {
+                anyhow::bail!("OWNER and REPO cannot be combined with --local");
+            }
+            return Ok(());
+        }
         if self.owner.is_none() {
             anyhow::bail!("Repository owner is required");
         }
-        if self.repo.is_none() {
-            anyhow::bail!("Repository name is required");
-        }
+        self.target()?;
         Ok(())
     }
 }
\ No newline at end of file
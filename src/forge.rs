@@ -0,0 +1,1201 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use crate::bitbucket::{BitbucketCloudClient, BitbucketServerClient};
+use crate::config::{Config, HostConfig};
+use crate::gitea::GiteaClient;
+use crate::github::GitHubClient;
+use crate::gitlab::GitLabClient;
+use crate::http::DEFAULT_CONCURRENCY;
+use crate::local::LocalRepoClient;
+use crate::report::{CommunityProfile, License, Popularity, RepositoryMetadata};
+use crate::types::{
+    CiActivity, CommitActivity, CommitInfo, ContributorActivity, IssueActivity, PullRequestActivity, ReleaseHistory, ReleaseInfo,
+    RepositoryInfo, SecurityData,
+};
+
+/// Error returned for a metric the forge doesn't have, e.g. issues on Bitbucket Server.
+/// The report marks such fields as unsupported instead of reporting zero.
+#[derive(Debug, Clone, Copy)]
+pub struct Unsupported {
+    pub forge: &'static str,
+    pub metric: &'static str,
+}
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not supported by {}", self.metric, self.forge)
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// Raw data fetched for a repository; counts are `None` when unsupported by the forge
+#[derive(Debug)]
+pub struct RepositoryData {
+    pub last_commit: CommitInfo,
+    pub commits_total: Option<usize>,
+    pub contributors_total: Option<usize>,
+    pub open_pull_requests: Option<usize>,
+    pub open_issues: Option<usize>,
+    pub latest_release: Option<ReleaseInfo>,
+    /// Commits per day over the last year, `None` when unsupported or failed
+    pub commit_activity: Option<CommitActivity>,
+    /// Commits per committer over the last year, `None` when unsupported or failed
+    pub contributor_activity: Option<ContributorActivity>,
+    /// Recent pull requests, `None` when not fetched, unsupported or failed
+    pub pull_request_activity: Option<PullRequestActivity>,
+    /// Recent issues, `None` when not fetched, unsupported or failed
+    pub issue_activity: Option<IssueActivity>,
+    /// Releases or version tags, `None` when unsupported or failed
+    pub release_history: Option<ReleaseHistory>,
+    /// CI results of the default branch, `None` when unsupported or failed
+    pub ci_activity: Option<CiActivity>,
+    /// Security policy, advisories and settings, `None` when unsupported or failed
+    pub security: Option<SecurityData>,
+    /// Community health files, `None` when unsupported or failed
+    pub community: Option<CommunityProfile>,
+    /// Detected license, `None` when unsupported or failed
+    pub license: Option<License>,
+    /// Stars, forks and watchers, `None` when unsupported or failed
+    pub popularity: Option<Popularity>,
+    /// Repositories depending on this one, `None` when not fetched, unsupported or failed
+    pub dependents: Option<usize>,
+    /// Repository flags, `None` when the forge client doesn't fetch them
+    pub metadata: Option<RepositoryMetadata>,
+    /// Names of the report fields the forge can't provide
+    pub unsupported: Vec<String>,
+    /// Report fields that failed to be fetched, with the error
+    pub errors: BTreeMap<String, String>,
+}
+
+/// How [`ForgeClient::fetch_repository`] fetches a repository
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// End of the activity windows, the time the report is scored at
+    pub now: DateTime<Utc>,
+    /// Fetch the CI results of the default branch, three requests
+    pub ci: bool,
+    /// Fetch the pull request and issue activity, which takes several requests
+    pub triage: bool,
+    /// Read the dependents count, which isn't in any API, from the web pages
+    pub dependents: bool,
+}
+
+impl FetchOptions {
+    /// Activity windows ending at `now`, without the optional sections
+    pub fn at(now: DateTime<Utc>) -> Self {
+        FetchOptions { now, ci: false, triage: false, dependents: false }
+    }
+
+    /// Activity windows ending at `now`, with the optional sections `config` asks for
+    pub fn for_config(config: &Config, now: DateTime<Utc>) -> Self {
+        FetchOptions {
+            // Scoring the CI results needs them
+            ci: config.ci || config.score_ci,
+            triage: config.triage,
+            dependents: config.dependents,
+            ..FetchOptions::at(now)
+        }
+    }
+}
+
+/// `fetch` when `enabled`, `None` otherwise, for sections that are opt-in
+pub(crate) async fn optional<T>(enabled: bool, fetch: impl Future<Output = T>) -> Option<T> {
+    if enabled { Some(fetch.await) } else { None }
+}
+
+/// Turns an [`Unsupported`] error into `None` and records the field name
+fn supported<T>(result: Result<T>, field: &str, unsupported: &mut Vec<String>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.downcast_ref::<Unsupported>().is_some() => {
+            unsupported.push(field.to_string());
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Like [`supported`], but a failed metric is recorded in `errors` instead of
+/// failing the whole repository
+pub(crate) fn metric<T>(
+    result: Result<T>,
+    field: &str,
+    unsupported: &mut Vec<String>,
+    errors: &mut BTreeMap<String, String>,
+) -> Option<T> {
+    supported(result, field, unsupported).unwrap_or_else(|e| {
+        errors.insert(field.to_string(), format!("{e:#}"));
+        None
+    })
+}
+
+/// Data source for a repository check. Every forge maps its API onto the
+/// GitHub-shaped [`CommitInfo`] and [`ReleaseInfo`] so scoring and output
+/// formats work unchanged. Metrics a forge doesn't have fail with [`Unsupported`].
+pub trait ForgeClient: Sync {
+    /// Human readable name of the forge, e.g. "GitHub"
+    fn name(&self) -> &'static str;
+
+    /// Latest commit on the default branch
+    fn get_last_commit(&self, owner: &str, repo: &str) -> impl Future<Output = Result<CommitInfo>> + Send;
+
+    /// Total number of commits on the default branch
+    fn get_commit_count(&self, owner: &str, repo: &str) -> impl Future<Output = Result<usize>> + Send;
+
+    /// Number of contributors
+    fn get_contributors_count(&self, owner: &str, repo: &str) -> impl Future<Output = Result<usize>> + Send;
+
+    /// Number of open pull requests (merge requests on GitLab)
+    fn get_open_prs_count(&self, owner: &str, repo: &str) -> impl Future<Output = Result<usize>> + Send;
+
+    /// Number of open issues, pull requests excluded
+    fn get_open_issues_count(&self, owner: &str, repo: &str) -> impl Future<Output = Result<usize>> + Send;
+
+    /// Latest published release, `None` if the repository has no releases
+    fn get_latest_release(&self, owner: &str, repo: &str)
+    -> impl Future<Output = Result<Option<ReleaseInfo>>> + Send;
+
+    /// Commits per day and per committer on the default branch over the
+    /// [`ACTIVITY_DAYS`](crate::types::ACTIVITY_DAYS) before `now`, the data of
+    /// the cadence and the bus factor. Both come from the same commits, so they
+    /// are fetched together, but each can fail on its own.
+    fn get_commit_history(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _now: DateTime<Utc>,
+    ) -> impl Future<Output = (Result<CommitActivity>, Result<ContributorActivity>)> + Send {
+        let forge = self.name();
+        async move {
+            (
+                Err(Unsupported { forge, metric: "Commit activity" }.into()),
+                Err(Unsupported { forge, metric: "Contributor activity" }.into()),
+            )
+        }
+    }
+
+    /// Pull requests and issues opened in the [`ACTIVITY_DAYS`](crate::types::ACTIVITY_DAYS)
+    /// before `now` and the ones closed since, with maintainer responses. Only
+    /// fetched with [`FetchOptions::triage`]; both share the comment listing on
+    /// some forges, but each can fail on its own.
+    fn get_triage_activity(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _now: DateTime<Utc>,
+    ) -> impl Future<Output = (Result<PullRequestActivity>, Result<IssueActivity>)> + Send {
+        let forge = self.name();
+        async move {
+            (
+                Err(Unsupported { forge, metric: "Pull request activity" }.into()),
+                Err(Unsupported { forge, metric: "Issue activity" }.into()),
+            )
+        }
+    }
+
+    /// Releases of the [`ACTIVITY_DAYS`](crate::types::ACTIVITY_DAYS) before
+    /// `now` and earlier, or version tags when the repository has no releases
+    fn get_release_history(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<ReleaseHistory>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "Release history" }.into()) }
+    }
+
+    /// State of the default branch head and its latest finished CI runs
+    fn get_ci_activity(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _repository: &RepositoryInfo,
+    ) -> impl Future<Output = Result<CiActivity>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "CI results" }.into()) }
+    }
+
+    /// Security policy, published advisories and security settings; settings
+    /// the credentials can't see are [`SecurityState::Unknown`](crate::report::SecurityState::Unknown)
+    fn get_security(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _repository: &RepositoryInfo,
+    ) -> impl Future<Output = Result<SecurityData>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "Security settings" }.into()) }
+    }
+
+    /// README, contributing guide and code of conduct, and their overall health
+    fn get_community(&self, _owner: &str, _repo: &str) -> impl Future<Output = Result<CommunityProfile>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "Community profile" }.into()) }
+    }
+
+    /// Detected license, [`License::none`] without one
+    fn get_license(&self, _owner: &str, _repo: &str) -> impl Future<Output = Result<License>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "License" }.into()) }
+    }
+
+    /// Number of repositories depending on this one, only fetched with [`FetchOptions::dependents`]
+    fn get_dependents_count(&self, _owner: &str, _repo: &str) -> impl Future<Output = Result<usize>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "Dependents count" }.into()) }
+    }
+
+    /// Archived, fork and other properties of the repository itself, its
+    /// popularity and the access of the credentials
+    fn get_repository(&self, _owner: &str, _repo: &str) -> impl Future<Output = Result<RepositoryInfo>> + Send {
+        let forge = self.name();
+        async move { Err(Unsupported { forge, metric: "Repository metadata" }.into()) }
+    }
+
+    /// Fetches everything a report needs
+    fn fetch_repository(
+        &self,
+        owner: &str,
+        repo: &str,
+        options: &FetchOptions,
+    ) -> impl Future<Output = Result<RepositoryData>> + Send {
+        fetch_metrics(self, owner, repo, options)
+    }
+
+    /// Number of repositories [`ForgeClient::fetch_repositories`] fetches at once
+    fn batch_size(&self) -> usize {
+        1
+    }
+
+    /// Fetches several repositories of this forge, results are in the order of `repos`.
+    /// Clients that can query many repositories in one request override this.
+    fn fetch_repositories(
+        &self,
+        repos: &[(&str, &str)],
+        options: &FetchOptions,
+    ) -> impl Future<Output = Vec<Result<RepositoryData>>> + Send {
+        async move {
+            let mut results = Vec::with_capacity(repos.len());
+            for (owner, repo) in repos {
+                results.push(self.fetch_repository(owner, repo, options).await);
+            }
+            results
+        }
+    }
+}
+
+/// Fetches a repository with the `get_*` methods, all of them at once; the
+/// client's transport limits how many requests are in flight. Only a failing
+/// last commit fails the repository, other metrics are recorded as errors.
+pub(crate) async fn fetch_metrics<F: ForgeClient + ?Sized>(
+    forge: &F,
+    owner: &str,
+    repo: &str,
+    options: &FetchOptions,
+) -> Result<RepositoryData> {
+    let (
+        last_commit,
+        commits_total,
+        contributors_total,
+        open_pull_requests,
+        open_issues,
+        latest_release,
+        (activity, committers),
+        triage,
+        releases,
+        (repository, ci, security),
+        community,
+        license,
+        dependents,
+    ) = tokio::join!(
+        forge.get_last_commit(owner, repo),
+        forge.get_commit_count(owner, repo),
+        forge.get_contributors_count(owner, repo),
+        forge.get_open_prs_count(owner, repo),
+        forge.get_open_issues_count(owner, repo),
+        forge.get_latest_release(owner, repo),
+        forge.get_commit_history(owner, repo, options.now),
+        optional(options.triage, forge.get_triage_activity(owner, repo, options.now)),
+        forge.get_release_history(owner, repo, options.now),
+        // CI and security need the default branch and permissions of the repository
+        async {
+            let repository = forge.get_repository(owner, repo).await;
+            let (ci, security) = match &repository {
+                Ok(info) => tokio::join!(
+                    optional(options.ci, forge.get_ci_activity(owner, repo, info)),
+                    forge.get_security(owner, repo, info),
+                ),
+                Err(e) => (options.ci.then(|| Err(shared_error(e))), Err(shared_error(e))),
+            };
+            (repository, ci, security)
+        },
+        forge.get_community(owner, repo),
+        forge.get_license(owner, repo),
+        optional(options.dependents, forge.get_dependents_count(owner, repo)),
+    );
+
+    let (pulls, issues) = triage.unzip();
+    let (metadata, popularity) = match repository {
+        Ok(info) => (Ok(info.metadata), Ok(info.popularity)),
+        Err(e) => (Err(shared_error(&e)), Err(e)),
+    };
+    let mut unsupported = Vec::new();
+    let mut errors = BTreeMap::new();
+    Ok(RepositoryData {
+        last_commit: last_commit?,
+        commits_total: metric(commits_total, "commits_total", &mut unsupported, &mut errors),
+        contributors_total: metric(contributors_total, "contributors_total", &mut unsupported, &mut errors),
+        open_pull_requests: metric(open_pull_requests, "open_pull_requests", &mut unsupported, &mut errors),
+        open_issues: metric(open_issues, "open_issues", &mut unsupported, &mut errors),
+        latest_release: metric(latest_release, "last_release", &mut unsupported, &mut errors).flatten(),
+        commit_activity: metric(activity, "cadence", &mut unsupported, &mut errors),
+        contributor_activity: metric(committers, "bus_factor", &mut unsupported, &mut errors),
+        pull_request_activity: pulls.and_then(|pulls| metric(pulls, "pull_requests", &mut unsupported, &mut errors)),
+        issue_activity: issues.and_then(|issues| metric(issues, "issues", &mut unsupported, &mut errors)),
+        release_history: metric(releases, "releases", &mut unsupported, &mut errors),
+        ci_activity: ci.and_then(|ci| metric(ci, "ci", &mut unsupported, &mut errors)),
+        security: metric(security, "security", &mut unsupported, &mut errors),
+        community: metric(community, "community", &mut unsupported, &mut errors),
+        license: metric(license, "license", &mut unsupported, &mut errors),
+        popularity: metric(popularity, "popularity", &mut unsupported, &mut errors),
+        dependents: dependents.and_then(|dependents| metric(dependents, "dependents", &mut unsupported, &mut errors)),
+        metadata: metric(metadata, "metadata", &mut unsupported, &mut errors),
+        unsupported,
+        errors,
+    })
+}
+
+/// Copy of an error failing several fields, still [`Unsupported`] if it was
+fn shared_error(e: &anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref::<Unsupported>() {
+        Some(unsupported) => (*unsupported).into(),
+        None => anyhow::anyhow!("{e:#}"),
+    }
+}
+
+/// Commit and contributor activity of one listing of `(committer, date)`
+/// pairs; a failed listing fails both
+pub(crate) fn from_commits(
+    commits: Result<impl Iterator<Item = (String, DateTime<Utc>)>>,
+) -> (Result<CommitActivity>, Result<ContributorActivity>) {
+    match commits {
+        Ok(commits) => {
+            let commits: Vec<_> = commits.collect();
+            (Ok(commits.iter().map(|(_, date)| *date).collect()), Ok(commits.into_iter().collect()))
+        }
+        Err(e) => {
+            let message = format!("{e:#}");
+            (Err(e), Err(anyhow::anyhow!(message)))
+        }
+    }
+}
+
+/// Forge answering from memory for tests. Repositories named "missing" don't
+/// exist, the `failing` metric errors and every `fetch_repositories` call is recorded.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct FakeForge {
+    pub batch_size: usize,
+    pub failing: Option<&'static str>,
+    pub fetches: std::sync::Mutex<Vec<usize>>,
+}
+
+#[cfg(test)]
+impl FakeForge {
+    fn check(&self, metric: &str) -> Result<()> {
+        anyhow::ensure!(self.failing != Some(metric), "{metric} failed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl ForgeClient for FakeForge {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn get_last_commit(&self, _owner: &str, repo: &str) -> Result<CommitInfo> {
+        anyhow::ensure!(repo != "missing", "Repository not found");
+        self.check("last_commit")?;
+        Ok(CommitInfo {
+            sha: "abc1234567".to_string(),
+            commit: crate::types::CommitMeta {
+                author: crate::types::AuthorMeta {
+                    name: "Test User".to_string(),
+                    email: "test@example.com".to_string(),
+                    date: chrono::Utc::now(),
+                },
+                message: "Test commit".to_string(),
+            },
+        })
+    }
+
+    async fn get_commit_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        self.check("commits_total").map(|_| 100)
+    }
+
+    async fn get_contributors_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        self.check("contributors_total").map(|_| 10)
+    }
+
+    async fn get_open_prs_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        self.check("open_pull_requests").map(|_| 5)
+    }
+
+    async fn get_open_issues_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        Err(Unsupported { forge: "fake", metric: "Issues" }.into())
+    }
+
+    async fn get_latest_release(&self, _owner: &str, _repo: &str) -> Result<Option<ReleaseInfo>> {
+        self.check("last_release").map(|_| None)
+    }
+
+    async fn get_commit_history(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _now: DateTime<Utc>,
+    ) -> (Result<CommitActivity>, Result<ContributorActivity>) {
+        (
+            self.check("cadence").map(|_| CommitActivity::default()),
+            self.check("bus_factor").map(|_| ContributorActivity::default()),
+        )
+    }
+
+    async fn get_triage_activity(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _now: DateTime<Utc>,
+    ) -> (Result<PullRequestActivity>, Result<IssueActivity>) {
+        (
+            self.check("pull_requests").map(|_| PullRequestActivity::default()),
+            self.check("issues").map(|_| IssueActivity::default()),
+        )
+    }
+
+    async fn get_release_history(&self, _owner: &str, _repo: &str, _now: DateTime<Utc>) -> Result<ReleaseHistory> {
+        self.check("releases").map(|_| ReleaseHistory::default())
+    }
+
+    async fn get_ci_activity(&self, _owner: &str, _repo: &str, _repository: &RepositoryInfo) -> Result<CiActivity> {
+        self.check("ci").map(|_| CiActivity::default())
+    }
+
+    async fn get_security(&self, _owner: &str, _repo: &str, _repository: &RepositoryInfo) -> Result<SecurityData> {
+        self.check("security").map(|_| SecurityData::default())
+    }
+
+    async fn get_community(&self, _owner: &str, _repo: &str) -> Result<CommunityProfile> {
+        self.check("community").map(|_| CommunityProfile::default())
+    }
+
+    async fn get_license(&self, _owner: &str, _repo: &str) -> Result<License> {
+        self.check("license").map(|_| License::none())
+    }
+
+    async fn get_dependents_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        self.check("dependents").map(|_| 0)
+    }
+
+    async fn get_repository(&self, _owner: &str, _repo: &str) -> Result<RepositoryInfo> {
+        self.check("metadata").map(|_| RepositoryInfo::default())
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size.max(1)
+    }
+
+    async fn fetch_repositories(&self, repos: &[(&str, &str)], options: &FetchOptions) -> Vec<Result<RepositoryData>> {
+        self.fetches.lock().unwrap().push(repos.len());
+        let mut results = Vec::new();
+        for (owner, repo) in repos {
+            results.push(self.fetch_repository(owner, repo, options).await);
+        }
+        results
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    /// Gitea and its forks: Forgejo, Codeberg
+    Gitea,
+    /// Bitbucket Cloud (bitbucket.org)
+    Bitbucket,
+    /// Bitbucket Server / Data Center
+    #[serde(rename = "bitbucket-server")]
+    BitbucketServer,
+}
+
+impl ForgeKind {
+    /// Recognizes well-known hosts; other hosts need a `forge` entry in the host config
+    fn for_host(host: &str, hosts: &BTreeMap<String, HostConfig>) -> Option<Self> {
+        if let Some(forge) = hosts.get(host).and_then(|h| h.forge) {
+            return Some(forge);
+        }
+        if host == "github.com" || host.starts_with("github.") {
+            Some(ForgeKind::GitHub)
+        } else if host == "gitlab.com" || host.starts_with("gitlab.") {
+            Some(ForgeKind::GitLab)
+        } else if host == "codeberg.org" || host.starts_with("gitea.") || host.starts_with("forgejo.") {
+            Some(ForgeKind::Gitea)
+        } else if host == "bitbucket.org" {
+            Some(ForgeKind::Bitbucket)
+        } else if host.starts_with("bitbucket.") {
+            Some(ForgeKind::BitbucketServer)
+        } else {
+            None
+        }
+    }
+
+    fn default_api_url(self, host: &str) -> String {
+        match self {
+            ForgeKind::GitHub if host == "github.com" => "https://api.github.com".to_string(),
+            ForgeKind::GitHub => format!("https://{host}/api/v3"),
+            ForgeKind::GitLab => format!("https://{host}/api/v4"),
+            ForgeKind::Gitea => format!("https://{host}/api/v1"),
+            ForgeKind::Bitbucket => "https://api.bitbucket.org/2.0".to_string(),
+            ForgeKind::BitbucketServer => format!("https://{host}/rest/api/1.0"),
+        }
+    }
+
+    fn token_env(self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GITHUB_TOKEN",
+            ForgeKind::GitLab => "GITLAB_TOKEN",
+            ForgeKind::Gitea => "GITEA_TOKEN",
+            ForgeKind::Bitbucket | ForgeKind::BitbucketServer => "BITBUCKET_TOKEN",
+        }
+    }
+}
+
+/// A repository on a specific forge host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoTarget {
+    pub forge: ForgeKind,
+    pub host: String,
+    /// Owner, or the full group path on GitLab (`group/subgroup`)
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RepoTarget {
+    pub fn github(owner: &str, repo: &str) -> Self {
+        RepoTarget {
+            forge: ForgeKind::GitHub,
+            host: "github.com".to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    /// Resolves the positional arguments: `OWNER REPO` for GitHub, or a single
+    /// repository spec, see [`RepoTarget::parse`].
+    pub fn from_args(owner: &str, repo: Option<&str>, hosts: &BTreeMap<String, HostConfig>) -> Result<Self> {
+        match repo {
+            Some(repo) => Ok(RepoTarget::github(owner, repo)),
+            None => RepoTarget::parse(owner, hosts),
+        }
+    }
+
+    /// Parses a repository spec and normalizes it to host/owner/repo. Accepted forms:
+    /// `owner/repo` (GitHub), `gitlab:group/project`, `codeberg:owner/repo`,
+    /// `bitbucket:workspace/repo`, `github:owner/repo`, web and clone URLs
+    /// (`https://host/owner/repo/tree/main`, `git@host:owner/repo.git`, `ssh://...`)
+    /// and URLs without a scheme (`host/owner/repo`).
+    /// Hosts are matched against `hosts` before the built-in list.
+    pub fn parse(spec: &str, hosts: &BTreeMap<String, HostConfig>) -> Result<Self> {
+        let spec = spec.trim();
+        if let Some(path) = spec.strip_prefix("gitlab:") {
+            return RepoTarget::from_path(ForgeKind::GitLab, "gitlab.com", path);
+        }
+        if let Some(path) = spec.strip_prefix("codeberg:") {
+            return RepoTarget::from_path(ForgeKind::Gitea, "codeberg.org", path);
+        }
+        if let Some(path) = spec.strip_prefix("bitbucket:") {
+            return RepoTarget::from_path(ForgeKind::Bitbucket, "bitbucket.org", path);
+        }
+        if let Some(path) = spec.strip_prefix("github:") {
+            return RepoTarget::from_path(ForgeKind::GitHub, "github.com", path);
+        }
+
+        let (host, path) = split_spec(spec)?;
+        let forge = ForgeKind::for_host(&host, hosts).with_context(|| {
+            format!("Unsupported forge host '{host}'. Set `forge` for it in the [hosts.\"{host}\"] config section")
+        })?;
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        match forge {
+            // Group paths nest, web pages live below /-/
+            ForgeKind::GitLab => {
+                let path = path.split("/-/").next().unwrap_or(path);
+                RepoTarget::from_path(forge, &host, path.strip_suffix(".git").unwrap_or(path))
+            }
+            // Web URLs look like /projects/KEY/repos/slug/browse, clone URLs like /scm/key/slug.git
+            ForgeKind::BitbucketServer => match parts.as_slice() {
+                ["projects", key, "repos", slug, ..] | ["scm", key, slug] => {
+                    RepoTarget::from_path(forge, &host, &format!("{key}/{slug}"))
+                }
+                _ => RepoTarget::from_path(forge, &host, path),
+            },
+            // Anything after owner/repo is a page of the repository (tree/main, issues, ...)
+            _ => match parts.as_slice() {
+                [owner, repo, ..] => RepoTarget::from_path(forge, &host, &format!("{owner}/{repo}")),
+                _ => anyhow::bail!("Expected owner/repo in '{spec}'"),
+            },
+        }
+    }
+
+    fn from_path(forge: ForgeKind, host: &str, path: &str) -> Result<Self> {
+        let path = path.trim_matches('/');
+        let (owner, repo) = path
+            .rsplit_once('/')
+            .filter(|(owner, repo)| !owner.is_empty() && !repo.is_empty())
+            .with_context(|| format!("Expected owner/repo in '{path}'"))?;
+        if forge != ForgeKind::GitLab && owner.contains('/') {
+            anyhow::bail!("Expected owner/repo in '{path}'");
+        }
+        Ok(RepoTarget {
+            forge,
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+/// Splits a spec that isn't a forge prefix into host and path
+fn split_spec(spec: &str) -> Result<(String, String)> {
+    let invalid = || {
+        format!("Invalid repository '{spec}'. Use OWNER REPO, owner/repo, gitlab:group/project or a repository URL")
+    };
+
+    if spec.contains("://") {
+        let url = Url::parse(spec).with_context(invalid)?;
+        if !matches!(url.scheme(), "https" | "http" | "ssh" | "git") {
+            anyhow::bail!("Unsupported URL scheme '{}' in '{spec}'", url.scheme());
+        }
+        let host = url.host_str().with_context(|| format!("Repository URL '{spec}' has no host"))?;
+        return Ok((host.to_lowercase(), url.path().to_string()));
+    }
+
+    // scp-like clone URL: git@host:owner/repo.git
+    if let Some((user_host, path)) = spec.split_once(':')
+        && let Some((_, host)) = user_host.split_once('@')
+        && !host.is_empty()
+        && !host.contains('/')
+    {
+        return Ok((host.to_lowercase(), path.to_string()));
+    }
+
+    let (first, rest) = spec.split_once('/').with_context(invalid)?;
+    if first.contains('.') || first.contains(':') {
+        // URL without a scheme: host/owner/repo
+        let url = Url::parse(&format!("https://{spec}")).with_context(invalid)?;
+        let host = url.host_str().with_context(invalid)?;
+        Ok((host.to_lowercase(), url.path().to_string()))
+    } else if !rest.contains('/') {
+        // Shorthand owner/repo
+        Ok(("github.com".to_string(), spec.to_string()))
+    } else {
+        anyhow::bail!("{}", invalid())
+    }
+}
+
+impl std::fmt::Display for RepoTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.forge, self.host.as_str()) {
+            (ForgeKind::GitHub, "github.com") => write!(f, "{}/{}", self.owner, self.repo),
+            (ForgeKind::GitLab, "gitlab.com") => write!(f, "gitlab:{}/{}", self.owner, self.repo),
+            (ForgeKind::Gitea, "codeberg.org") => write!(f, "codeberg:{}/{}", self.owner, self.repo),
+            (ForgeKind::Bitbucket, "bitbucket.org") => write!(f, "bitbucket:{}/{}", self.owner, self.repo),
+            (_, host) => write!(f, "https://{host}/{}/{}", self.owner, self.repo),
+        }
+    }
+}
+
+/// A forge client chosen at runtime
+pub enum AnyForge {
+    GitHub(GitHubClient),
+    GitLab(GitLabClient),
+    Gitea(GiteaClient),
+    Bitbucket(BitbucketCloudClient),
+    BitbucketServer(BitbucketServerClient),
+    Local(LocalRepoClient),
+}
+
+impl AnyForge {
+    /// Creates a client for the forge hosting `target`. API root and token come
+    /// from the host's `[hosts]` config entry; the token falls back to
+    /// `GITHUB_TOKEN`, `GITLAB_TOKEN`, `GITEA_TOKEN` or `BITBUCKET_TOKEN`.
+    pub fn connect(target: &RepoTarget, config: &Config) -> Result<Self> {
+        let host_config = config.hosts.get(&target.host).cloned().unwrap_or_default();
+        let api_url = host_config
+            .api_url
+            .clone()
+            .unwrap_or_else(|| target.forge.default_api_url(&target.host));
+        let token = host_config
+            .get_token()
+            .or_else(|| std::env::var(target.forge.token_env()).ok());
+        let concurrency = config.concurrency.unwrap_or(DEFAULT_CONCURRENCY);
+        let cache = config.response_cache();
+        let cassette = config.cassette()?;
+
+        match target.forge {
+            ForgeKind::GitHub => {
+                let mut builder = GitHubClient::builder().base_url(api_url).concurrency(concurrency);
+                if let Some(token) = token {
+                    builder = builder.token(token);
+                }
+                if let Some(cache) = &cache {
+                    builder = builder.cache(cache.clone());
+                }
+                if let Some(cassette) = &cassette {
+                    builder = builder.cassette(cassette.clone());
+                }
+                Ok(AnyForge::GitHub(builder.build()?))
+            }
+            ForgeKind::GitLab => {
+                let mut builder = GitLabClient::builder().base_url(api_url).concurrency(concurrency);
+                if let Some(token) = token {
+                    builder = builder.token(token);
+                }
+                if let Some(cache) = &cache {
+                    builder = builder.cache(cache.clone());
+                }
+                if let Some(cassette) = &cassette {
+                    builder = builder.cassette(cassette.clone());
+                }
+                Ok(AnyForge::GitLab(builder.build()?))
+            }
+            ForgeKind::Gitea => {
+                let mut builder = GiteaClient::builder().base_url(api_url).concurrency(concurrency);
+                if let Some(token) = token {
+                    builder = builder.token(token);
+                }
+                if let Some(cache) = &cache {
+                    builder = builder.cache(cache.clone());
+                }
+                if let Some(cassette) = &cassette {
+                    builder = builder.cassette(cassette.clone());
+                }
+                Ok(AnyForge::Gitea(builder.build()?))
+            }
+            ForgeKind::Bitbucket => {
+                let mut builder = BitbucketCloudClient::builder().base_url(api_url).concurrency(concurrency);
+                if let Some(token) = token {
+                    builder = builder.token(token);
+                }
+                if let Some(cache) = &cache {
+                    builder = builder.cache(cache.clone());
+                }
+                if let Some(cassette) = &cassette {
+                    builder = builder.cassette(cassette.clone());
+                }
+                Ok(AnyForge::Bitbucket(builder.build()?))
+            }
+            ForgeKind::BitbucketServer => {
+                let mut builder = BitbucketServerClient::builder().base_url(api_url).concurrency(concurrency);
+                if let Some(token) = token {
+                    builder = builder.token(token);
+                }
+                if let Some(cache) = &cache {
+                    builder = builder.cache(cache.clone());
+                }
+                if let Some(cassette) = &cassette {
+                    builder = builder.cassette(cassette.clone());
+                }
+                Ok(AnyForge::BitbucketServer(builder.build_server()?))
+            }
+        }
+    }
+}
+
+impl ForgeClient for AnyForge {
+    fn name(&self) -> &'static str {
+        match self {
+            AnyForge::GitHub(client) => client.name(),
+            AnyForge::GitLab(client) => client.name(),
+            AnyForge::Gitea(client) => client.name(),
+            AnyForge::Bitbucket(client) => client.name(),
+            AnyForge::BitbucketServer(client) => client.name(),
+            AnyForge::Local(client) => client.name(),
+        }
+    }
+
+    async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
+        match self {
+            AnyForge::GitHub(client) => client.get_last_commit(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_last_commit(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_last_commit(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_last_commit(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_last_commit(owner, repo).await,
+            AnyForge::Local(client) => client.get_last_commit(owner, repo).await,
+        }
+    }
+
+    async fn get_commit_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        match self {
+            AnyForge::GitHub(client) => client.get_commit_count(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_commit_count(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_commit_count(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_commit_count(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_commit_count(owner, repo).await,
+            AnyForge::Local(client) => client.get_commit_count(owner, repo).await,
+        }
+    }
+
+    async fn get_contributors_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        match self {
+            AnyForge::GitHub(client) => client.get_contributors_count(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_contributors_count(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_contributors_count(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_contributors_count(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_contributors_count(owner, repo).await,
+            AnyForge::Local(client) => client.get_contributors_count(owner, repo).await,
+        }
+    }
+
+    async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        match self {
+            AnyForge::GitHub(client) => client.get_open_prs_count(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_open_prs_count(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_open_prs_count(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_open_prs_count(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_open_prs_count(owner, repo).await,
+            AnyForge::Local(client) => client.get_open_prs_count(owner, repo).await,
+        }
+    }
+
+    async fn get_open_issues_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        match self {
+            AnyForge::GitHub(client) => client.get_open_issues_count(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_open_issues_count(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_open_issues_count(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_open_issues_count(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_open_issues_count(owner, repo).await,
+            AnyForge::Local(client) => client.get_open_issues_count(owner, repo).await,
+        }
+    }
+
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
+        match self {
+            AnyForge::GitHub(client) => client.get_latest_release(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_latest_release(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_latest_release(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_latest_release(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_latest_release(owner, repo).await,
+            AnyForge::Local(client) => client.get_latest_release(owner, repo).await,
+        }
+    }
+
+    async fn get_commit_history(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<CommitActivity>, Result<ContributorActivity>) {
+        match self {
+            AnyForge::GitHub(client) => client.get_commit_history(owner, repo, now).await,
+            AnyForge::GitLab(client) => client.get_commit_history(owner, repo, now).await,
+            AnyForge::Gitea(client) => client.get_commit_history(owner, repo, now).await,
+            AnyForge::Bitbucket(client) => client.get_commit_history(owner, repo, now).await,
+            AnyForge::BitbucketServer(client) => client.get_commit_history(owner, repo, now).await,
+            AnyForge::Local(client) => client.get_commit_history(owner, repo, now).await,
+        }
+    }
+
+    async fn get_triage_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<PullRequestActivity>, Result<IssueActivity>) {
+        match self {
+            AnyForge::GitHub(client) => client.get_triage_activity(owner, repo, now).await,
+            AnyForge::GitLab(client) => client.get_triage_activity(owner, repo, now).await,
+            AnyForge::Gitea(client) => client.get_triage_activity(owner, repo, now).await,
+            AnyForge::Bitbucket(client) => client.get_triage_activity(owner, repo, now).await,
+            AnyForge::BitbucketServer(client) => client.get_triage_activity(owner, repo, now).await,
+            AnyForge::Local(client) => client.get_triage_activity(owner, repo, now).await,
+        }
+    }
+
+    async fn get_release_history(&self, owner: &str, repo: &str, now: DateTime<Utc>) -> Result<ReleaseHistory> {
+        match self {
+            AnyForge::GitHub(client) => client.get_release_history(owner, repo, now).await,
+            AnyForge::GitLab(client) => client.get_release_history(owner, repo, now).await,
+            AnyForge::Gitea(client) => client.get_release_history(owner, repo, now).await,
+            AnyForge::Bitbucket(client) => client.get_release_history(owner, repo, now).await,
+            AnyForge::BitbucketServer(client) => client.get_release_history(owner, repo, now).await,
+            AnyForge::Local(client) => client.get_release_history(owner, repo, now).await,
+        }
+    }
+
+    async fn get_ci_activity(&self, owner: &str, repo: &str, repository: &RepositoryInfo) -> Result<CiActivity> {
+        match self {
+            AnyForge::GitHub(client) => client.get_ci_activity(owner, repo, repository).await,
+            AnyForge::GitLab(client) => client.get_ci_activity(owner, repo, repository).await,
+            AnyForge::Gitea(client) => client.get_ci_activity(owner, repo, repository).await,
+            AnyForge::Bitbucket(client) => client.get_ci_activity(owner, repo, repository).await,
+            AnyForge::BitbucketServer(client) => client.get_ci_activity(owner, repo, repository).await,
+            AnyForge::Local(client) => client.get_ci_activity(owner, repo, repository).await,
+        }
+    }
+
+    async fn get_security(&self, owner: &str, repo: &str, repository: &RepositoryInfo) -> Result<SecurityData> {
+        match self {
+            AnyForge::GitHub(client) => client.get_security(owner, repo, repository).await,
+            AnyForge::GitLab(client) => client.get_security(owner, repo, repository).await,
+            AnyForge::Gitea(client) => client.get_security(owner, repo, repository).await,
+            AnyForge::Bitbucket(client) => client.get_security(owner, repo, repository).await,
+            AnyForge::BitbucketServer(client) => client.get_security(owner, repo, repository).await,
+            AnyForge::Local(client) => client.get_security(owner, repo, repository).await,
+        }
+    }
+
+    async fn get_community(&self, owner: &str, repo: &str) -> Result<CommunityProfile> {
+        match self {
+            AnyForge::GitHub(client) => client.get_community(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_community(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_community(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_community(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_community(owner, repo).await,
+            AnyForge::Local(client) => client.get_community(owner, repo).await,
+        }
+    }
+
+    async fn get_license(&self, owner: &str, repo: &str) -> Result<License> {
+        match self {
+            AnyForge::GitHub(client) => client.get_license(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_license(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_license(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_license(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_license(owner, repo).await,
+            AnyForge::Local(client) => client.get_license(owner, repo).await,
+        }
+    }
+
+    async fn get_dependents_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        match self {
+            AnyForge::GitHub(client) => client.get_dependents_count(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_dependents_count(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_dependents_count(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_dependents_count(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_dependents_count(owner, repo).await,
+            AnyForge::Local(client) => client.get_dependents_count(owner, repo).await,
+        }
+    }
+
+    async fn get_repository(&self, owner: &str, repo: &str) -> Result<RepositoryInfo> {
+        match self {
+            AnyForge::GitHub(client) => client.get_repository(owner, repo).await,
+            AnyForge::GitLab(client) => client.get_repository(owner, repo).await,
+            AnyForge::Gitea(client) => client.get_repository(owner, repo).await,
+            AnyForge::Bitbucket(client) => client.get_repository(owner, repo).await,
+            AnyForge::BitbucketServer(client) => client.get_repository(owner, repo).await,
+            AnyForge::Local(client) => client.get_repository(owner, repo).await,
+        }
+    }
+
+    async fn fetch_repository(&self, owner: &str, repo: &str, options: &FetchOptions) -> Result<RepositoryData> {
+        match self {
+            AnyForge::GitHub(client) => client.fetch_repository(owner, repo, options).await,
+            AnyForge::GitLab(client) => client.fetch_repository(owner, repo, options).await,
+            AnyForge::Gitea(client) => client.fetch_repository(owner, repo, options).await,
+            AnyForge::Bitbucket(client) => client.fetch_repository(owner, repo, options).await,
+            AnyForge::BitbucketServer(client) => client.fetch_repository(owner, repo, options).await,
+            AnyForge::Local(client) => client.fetch_repository(owner, repo, options).await,
+        }
+    }
+
+    fn batch_size(&self) -> usize {
+        match self {
+            AnyForge::GitHub(client) => client.batch_size(),
+            AnyForge::GitLab(client) => client.batch_size(),
+            AnyForge::Gitea(client) => client.batch_size(),
+            AnyForge::Bitbucket(client) => client.batch_size(),
+            AnyForge::BitbucketServer(client) => client.batch_size(),
+            AnyForge::Local(client) => client.batch_size(),
+        }
+    }
+
+    async fn fetch_repositories(&self, repos: &[(&str, &str)], options: &FetchOptions) -> Vec<Result<RepositoryData>> {
+        match self {
+            AnyForge::GitHub(client) => client.fetch_repositories(repos, options).await,
+            AnyForge::GitLab(client) => client.fetch_repositories(repos, options).await,
+            AnyForge::Gitea(client) => client.fetch_repositories(repos, options).await,
+            AnyForge::Bitbucket(client) => client.fetch_repositories(repos, options).await,
+            AnyForge::BitbucketServer(client) => client.fetch_repositories(repos, options).await,
+            AnyForge::Local(client) => client.fetch_repositories(repos, options).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_args() {
+        let hosts = BTreeMap::new();
+        let target = RepoTarget::from_args("rust-lang", Some("rust"), &hosts).unwrap();
+        assert_eq!(target, RepoTarget::github("rust-lang", "rust"));
+
+        let target = RepoTarget::from_args("gitlab:gitlab-org/gitlab-runner", None, &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::GitLab);
+        assert_eq!(target.host, "gitlab.com");
+        assert_eq!(target.owner, "gitlab-org");
+        assert_eq!(target.repo, "gitlab-runner");
+        assert_eq!(target.to_string(), "gitlab:gitlab-org/gitlab-runner");
+
+        let target = RepoTarget::from_args("codeberg:forgejo/forgejo", None, &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::Gitea);
+        assert_eq!(target.host, "codeberg.org");
+        assert_eq!(target.to_string(), "codeberg:forgejo/forgejo");
+    }
+
+    #[test]
+    fn test_from_url() {
+        let hosts = BTreeMap::new();
+        let target = RepoTarget::from_args("https://gitlab.example.com/group/sub/project.git", None, &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::GitLab);
+        assert_eq!(target.host, "gitlab.example.com");
+        assert_eq!(target.owner, "group/sub");
+        assert_eq!(target.repo, "project");
+
+        let target = RepoTarget::from_args("https://github.com/rust-lang/rust/", None, &hosts).unwrap();
+        assert_eq!(target, RepoTarget::github("rust-lang", "rust"));
+
+        assert!(RepoTarget::from_args("https://example.com/a/b", None, &hosts).is_err());
+        assert!(RepoTarget::from_args("https://codeberg.org/a", None, &hosts).is_err());
+        assert!(RepoTarget::from_args("gitlab:project", None, &hosts).is_err());
+        assert!(RepoTarget::from_args("rust-lang", None, &hosts).is_err());
+    }
+
+    #[test]
+    fn test_parse_specs() {
+        let hosts = BTreeMap::new();
+        let rust = RepoTarget::github("rust-lang", "rust");
+        for spec in [
+            "rust-lang/rust",
+            "https://github.com/rust-lang/rust",
+            "http://github.com/rust-lang/rust.git",
+            "git@github.com:rust-lang/rust.git",
+            "ssh://git@github.com/rust-lang/rust.git",
+            "github.com/rust-lang/rust/tree/master/src",
+            "https://github.com/rust-lang/rust/issues/123",
+            " GitHub.com/rust-lang/rust ",
+        ] {
+            assert_eq!(RepoTarget::parse(spec, &hosts).unwrap(), rust, "{spec}");
+        }
+
+        let target = RepoTarget::parse("git@github.example.com:platform/api.git", &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::GitHub);
+        assert_eq!(target.host, "github.example.com");
+        assert_eq!(target.forge.default_api_url(&target.host), "https://github.example.com/api/v3");
+
+        let target = RepoTarget::parse("https://gitlab.com/group/sub/project/-/tree/main", &hosts).unwrap();
+        assert_eq!(target.owner, "group/sub");
+        assert_eq!(target.repo, "project");
+
+        let target = RepoTarget::parse("codeberg.org/forgejo/forgejo/src/branch/forgejo", &hosts).unwrap();
+        assert_eq!(target.to_string(), "codeberg:forgejo/forgejo");
+
+        let target = RepoTarget::parse("ssh://git@bitbucket.example.com:7999/scm/tools/cli.git", &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::BitbucketServer);
+        assert_eq!((target.owner.as_str(), target.repo.as_str()), ("tools", "cli"));
+
+        let err = RepoTarget::parse("ftp://github.com/a/b", &hosts).unwrap_err();
+        assert!(err.to_string().contains("Unsupported URL scheme"));
+        let err = RepoTarget::parse("git@example.com:a/b.git", &hosts).unwrap_err();
+        assert!(err.to_string().contains("Unsupported forge host 'example.com'"));
+        assert!(RepoTarget::parse("a/b/c", &hosts).is_err());
+    }
+
+    #[test]
+    fn test_configured_host() {
+        let mut hosts = BTreeMap::new();
+        hosts.insert(
+            "git.example.com".to_string(),
+            HostConfig {
+                forge: Some(ForgeKind::Gitea),
+                ..Default::default()
+            },
+        );
+
+        let target = RepoTarget::from_args("https://git.example.com/team/tool", None, &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::Gitea);
+        assert_eq!(target.to_string(), "https://git.example.com/team/tool");
+        assert_eq!(target.forge.default_api_url(&target.host), "https://git.example.com/api/v1");
+    }
+
+    #[test]
+    fn test_bitbucket_targets() {
+        let hosts = BTreeMap::new();
+        let target = RepoTarget::from_args("https://bitbucket.org/atlassian/python-bitbucket", None, &hosts).unwrap();
+        assert_eq!(target.forge, ForgeKind::Bitbucket);
+        assert_eq!(target.to_string(), "bitbucket:atlassian/python-bitbucket");
+
+        let target =
+            RepoTarget::from_args("https://bitbucket.example.com/projects/TOOLS/repos/cli/browse", None, &hosts)
+                .unwrap();
+        assert_eq!(target.forge, ForgeKind::BitbucketServer);
+        assert_eq!(target.owner, "TOOLS");
+        assert_eq!(target.repo, "cli");
+    }
+
+    #[test]
+    fn test_supported() {
+        let mut unsupported = Vec::new();
+        assert_eq!(supported(Ok(3), "open_issues", &mut unsupported).unwrap(), Some(3));
+
+        let missing: Result<usize> = Err(Unsupported { forge: "Test", metric: "Issues" }.into());
+        assert_eq!(supported(missing, "open_issues", &mut unsupported).unwrap(), None);
+        assert_eq!(unsupported, vec!["open_issues".to_string()]);
+
+        let failed: Result<usize> = Err(anyhow::anyhow!("network error"));
+        assert!(supported(failed, "open_pull_requests", &mut unsupported).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_metrics_collects_errors() {
+        let forge = FakeForge { failing: Some("commits_total"), ..Default::default() };
+        let data = forge.fetch_repository("test", "repo", &FetchOptions::at(Utc::now())).await.unwrap();
+        assert_eq!(data.commits_total, None);
+        assert_eq!(data.contributors_total, Some(10));
+        assert_eq!(data.errors["commits_total"], "commits_total failed");
+        assert_eq!(data.unsupported, vec!["open_issues".to_string()]);
+        // CI results, pull requests and issues are only fetched on request
+        assert_eq!((data.ci_activity, data.pull_request_activity), (None, None));
+        let forge = FakeForge { failing: Some("issues"), ..Default::default() };
+        let options = FetchOptions { triage: true, ..FetchOptions::at(Utc::now()) };
+        let data = forge.fetch_repository("test", "repo", &options).await.unwrap();
+        assert_eq!(data.pull_request_activity, Some(PullRequestActivity::default()));
+        assert_eq!(data.issue_activity, None);
+        assert_eq!(data.errors["issues"], "issues failed");
+        // CI and security need the repository, they fail with it
+        let forge = FakeForge { failing: Some("metadata"), ..Default::default() };
+        let data = forge.fetch_repository("test", "repo", &FetchOptions::at(Utc::now())).await.unwrap();
+        assert_eq!((data.metadata, data.popularity, data.ci_activity, data.security), (None, None, None, None));
+        let failed: Vec<&str> = data.errors.keys().map(String::as_str).collect();
+        assert_eq!(failed, vec!["metadata", "popularity", "security"]);
+        let options = FetchOptions { ci: true, ..FetchOptions::at(Utc::now()) };
+        let data = forge.fetch_repository("test", "repo", &options).await.unwrap();
+        assert!(data.errors.contains_key("ci"));
+
+        let forge = FakeForge { failing: Some("last_commit"), ..Default::default() };
+        assert!(forge.fetch_repository("test", "repo", &FetchOptions::at(Utc::now())).await.is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, header};
+use std::collections::HashSet;
+
+use crate::forge::ForgeClient;
+use crate::http::{Cassette, DEFAULT_CONCURRENCY, HttpResponse, ResponseCache, Transport};
+use crate::types::{CommitInfo, ReleaseInfo, RepositoryInfo, RepositoryResp};
+
+static DEFAULT_BASE_URL: &str = "https://codeberg.org/api/v1";
+static DEFAULT_USER_AGENT: &str = "github-activity-check/0.1";
+
+/// Commits scanned for distinct authors, Gitea has no contributors endpoint
+const CONTRIBUTOR_PAGES: usize = 20;
+const PAGE_SIZE: usize = 50;
+
+/// Client for the Gitea API v1, also served by Forgejo and Codeberg
+pub struct GiteaClient {
+    http: Transport,
+    base_url: String,
+}
+
+/// Builder for [`GiteaClient`]
+#[derive(Default)]
+pub struct GiteaClientBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    concurrency: Option<usize>,
+    cache: Option<ResponseCache>,
+    cassette: Option<Cassette>,
+}
+
+impl GiteaClientBuilder {
+    /// Access token, sent as `Authorization: token`
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// API root, e.g. `https://gitea.example.com/api/v1` (default: `https://codeberg.org/api/v1`)
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Maximum number of requests in flight (default: 4)
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Caches responses on disk and revalidates them with conditional requests
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Records every exchange into the cassette, or serves them from it
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    pub fn build(self) -> Result<GiteaClient> {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::USER_AGENT, header::HeaderValue::from_static(DEFAULT_USER_AGENT));
+        headers.insert(header::ACCEPT, header::HeaderValue::from_static("application/json"));
+        if let Some(t) = &self.token {
+            headers.insert(header::AUTHORIZATION, header::HeaderValue::from_str(&format!("token {t}"))?);
+        }
+        let client = Client::builder().default_headers(headers).build()?;
+        let http = Transport::new(client, self.concurrency.unwrap_or(DEFAULT_CONCURRENCY))
+            .with_cache(self.cache.clone(), self.token.as_deref())
+            .with_cassette(self.cassette.clone());
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/').to_string();
+        Ok(GiteaClient { http, base_url })
+    }
+}
+
+impl GiteaClient {
+    pub fn builder() -> GiteaClientBuilder {
+        GiteaClientBuilder::default()
+    }
+
+    async fn get(&self, url: &str, owner: &str, repo: &str) -> Result<HttpResponse> {
+        self.http.send(self.http.get(url)).await
+            .with_context(|| format!("Failed to fetch data from {url}"))?
+            .error_for_status()
+            .with_context(|| format!("Gitea API error for repository {owner}/{repo}"))
+    }
+
+    /// Reads the `X-Total-Count` header of a listing
+    async fn fetch_total_count(&self, path_with_query: &str, owner: &str, repo: &str) -> Result<usize> {
+        let url = format!("{}/repos/{owner}/{repo}{path_with_query}", self.base_url);
+        let resp = self.get(&url, owner, repo).await?;
+        resp.headers()
+            .get("x-total-count")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .with_context(|| format!("Gitea did not report a total for {url}"))
+    }
+}
+
+impl ForgeClient for GiteaClient {
+    fn name(&self) -> &'static str {
+        "Gitea"
+    }
+
+    async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
+        let url = format!(
+            "{}/repos/{owner}/{repo}/commits?limit=1&stat=false&verification=false&files=false",
+            self.base_url
+        );
+        // Gitea commits have the same shape as GitHub commits
+        let mut items: Vec<CommitInfo> = self.get(&url, owner, repo).await?.json()
+            .context("Failed to parse commit response as JSON")?;
+        items.pop().with_context(|| format!("Repository {owner}/{repo} has no commits"))
+    }
+
+    async fn get_commit_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        self.fetch_total_count("/commits?limit=1&stat=false&verification=false&files=false", owner, repo)
+            .await
+    }
+
+    /// Distinct commit authors among the latest 1000 commits
+    async fn get_contributors_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        let mut authors = HashSet::new();
+        for page in 1..=CONTRIBUTOR_PAGES {
+            let url = format!(
+                "{}/repos/{owner}/{repo}/commits?limit={PAGE_SIZE}&page={page}&stat=false&verification=false&files=false",
+                self.base_url
+            );
+            let items: Vec<CommitInfo> = self.get(&url, owner, repo).await?.json()
+                .context("Failed to parse commit response as JSON")?;
+            let count = items.len();
+            authors.extend(items.into_iter().map(|c| c.commit.author.email.to_lowercase()));
+            if count < PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(authors.len())
+    }
+
+    async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        self.fetch_total_count("/pulls?state=open&limit=1", owner, repo).await
+    }
+
+    async fn get_open_issues_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        self.fetch_total_count("/issues?state=open&type=issues&limit=1", owner, repo).await
+    }
+
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
+        let url = format!("{}/repos/{owner}/{repo}/releases/latest", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch latest release from {url}"))?;
+
+        // Gitea returns 404 if no releases exist
+        if resp.status() == 404 {
+            return Ok(None);
+        }
+
+        let resp = resp.error_for_status()
+            .with_context(|| format!("Latest release API error for repository {owner}/{repo}"))?;
+        let release: ReleaseInfo = resp.json()
+            .context("Failed to parse latest release response")?;
+        Ok(Some(release))
+    }
+
+    async fn get_repository(&self, owner: &str, repo: &str) -> Result<RepositoryInfo> {
+        let url = format!("{}/repos/{owner}/{repo}", self.base_url);
+        let repository: RepositoryResp = self.get(&url, owner, repo).await?.json()
+            .context("Failed to parse repository response")?;
+        Ok(repository.info(owner, repo))
+    }
+}
@@ -1,47 +1,495 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
 use reqwest::{Client, header};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::types::{CommitInfo, ReleaseInfo, SearchCommitsResp, SearchIssuesResp};
+use crate::forge::{FetchOptions, ForgeClient, RepositoryData, fetch_metrics, from_commits, metric, optional};
+use crate::http::{Cassette, DEFAULT_CONCURRENCY, HttpResponse, ResponseCache, Transport};
+use crate::report::{CommunityProfile, License, SecurityState};
+use crate::types::{
+    ACTIVITY_DAYS, ACTIVITY_MAX_PAGES, ActivityWeekResp, AdvisoryInfo, AdvisoryResp, BranchResp, CI_RUNS_MAX,
+    CheckRunsResp, CiActivity, CombinedStatusResp, CommentResp, CommunityProfileResp, CommitActivity, CommitInfo,
+    ContributorActivity, ContributorStatsResp, IssueActivity, IssueResp, LicenseFileResp, PullRequestActivity,
+    PullRequestInfo, PullResp, ReleaseHistory, ReleaseInfo, RepositoryInfo, RepositoryResp, SearchCommitsResp,
+    SearchIssuesResp, SecurityData, TAG_HISTORY_MAX, TagResp, TreeResp, Version, WorkflowRunResp, WorkflowRunsResp,
+};
 
-static BASE: &str = "https://api.github.com";
+mod graphql;
 
+/// Places GitHub looks for a security policy, in order
+const SECURITY_POLICY_PATHS: [&str; 3] = [".github/SECURITY.md", "SECURITY.md", "docs/SECURITY.md"];
+/// Places of the Dependabot version updates configuration
+const DEPENDABOT_CONFIG_PATHS: [&str; 2] = [".github/dependabot.yml", ".github/dependabot.yaml"];
+
+static DEFAULT_BASE_URL: &str = "https://api.github.com";
+static DEFAULT_USER_AGENT: &str = "github-activity-check/0.1";
+/// Repositories aliased into one GraphQL query
+const GRAPHQL_BATCH_SIZE: usize = 20;
+
+/// Client for the GitHub API
+///
+/// With a token, one GraphQL query covers the counts, last commit, latest
+/// release and repository properties of up to 20 repositories. Each
+/// repository still takes about ten REST requests for the sections the
+/// query doesn't have: contributors, commit statistics, releases, security
+/// settings, community profile and license. CI results add three requests,
+/// pull requests and issues several listings and the dependents a web page,
+/// all only on request.
+/// Without a token everything goes through REST, as GraphQL requires
+/// authentication.
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// use github_activity_check::GitHubClient;
+///
+/// let client = GitHubClient::builder()
+///     .token(std::env::var("GITHUB_TOKEN")?)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
 pub struct GitHubClient {
-    client: Client,
+    http: Transport,
+    /// Web pages, requested without the token
+    web: Transport,
+    base_url: String,
+    /// GraphQL endpoint, `None` when only REST is used
+    graphql_url: Option<String>,
 }
 
-impl GitHubClient {
-    pub fn new(token: Option<&str>) -> Result<Self> {
+/// Builder for [`GitHubClient`]
+#[derive(Default)]
+pub struct GitHubClientBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    user_agent: Option<String>,
+    rest_only: bool,
+    concurrency: Option<usize>,
+    cache: Option<ResponseCache>,
+    cassette: Option<Cassette>,
+}
+
+impl GitHubClientBuilder {
+    /// Token sent as `Authorization: Bearer`, raises the rate limit from 60 to 5000 requests/hour
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// API root, e.g. `https://github.example.com/api/v3` for GitHub Enterprise (default: `https://api.github.com`)
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Maximum number of requests in flight (default: 4)
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Caches responses on disk and revalidates them with conditional requests
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Records every exchange into the cassette, or serves them from it
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// User agent sent with every request (default: `github-activity-check/0.1`)
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Uses only the REST API even when a token is set
+    pub fn rest_only(mut self) -> Self {
+        self.rest_only = true;
+        self
+    }
+
+    pub fn build(self) -> Result<GitHubClient> {
         let mut headers = header::HeaderMap::new();
         headers.insert(
             header::USER_AGENT,
-            header::HeaderValue::from_static("github-activity-check/0.1"),
+            header::HeaderValue::from_str(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+                .context("Invalid user agent")?,
         );
+        let concurrency = self.concurrency.unwrap_or(DEFAULT_CONCURRENCY);
+        // The web host gets neither the token nor the API media type
+        let web = Client::builder().default_headers(headers.clone()).build()?;
+        let web = Transport::new(web, concurrency).with_cache(self.cache.clone(), None).with_cassette(self.cassette.clone());
         headers.insert(
             header::ACCEPT,
             header::HeaderValue::from_static("application/vnd.github+json"),
         );
-        if let Some(t) = token {
+        if let Some(t) = &self.token {
             headers.insert(
                 header::AUTHORIZATION,
                 header::HeaderValue::from_str(&format!("Bearer {t}"))?,
             );
         }
         let client = Client::builder().default_headers(headers).build()?;
-        Ok(GitHubClient { client })
+        let http = Transport::new(client, concurrency)
+            .with_cache(self.cache.clone(), self.token.as_deref())
+            .with_cassette(self.cassette.clone());
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/').to_string();
+        let graphql_url = (self.token.is_some() && !self.rest_only).then(|| graphql_url(&base_url));
+        Ok(GitHubClient { http, web, base_url, graphql_url })
+    }
+}
+
+impl GitHubClient {
+    pub fn builder() -> GitHubClientBuilder {
+        GitHubClientBuilder::default()
+    }
+
+    /// Shorthand for a client with default settings and an optional token
+    pub fn new(token: Option<&str>) -> Result<Self> {
+        let mut builder = Self::builder();
+        if let Some(t) = token {
+            builder = builder.token(t);
+        }
+        builder.build()
+    }
+
+    /// Fetches up to [`GRAPHQL_BATCH_SIZE`] repositories with one GraphQL query
+    async fn fetch_graphql(
+        &self,
+        url: &str,
+        repos: &[(&str, &str)],
+        options: &FetchOptions,
+    ) -> Vec<Result<RepositoryData>> {
+        let response = async {
+            let resp = self.http.send(self.http.post(url).json(&graphql::query(repos))).await
+                .with_context(|| format!("Failed to query {url}"))?
+                .error_for_status()
+                .context("GitHub GraphQL API error")?;
+            resp.json::<serde_json::Value>().context("Failed to parse GraphQL response as JSON")
+        }
+        .await;
+        let body = match response {
+            Ok(body) => body,
+            Err(e) => return repos.iter().map(|_| Err(anyhow::anyhow!("{e:#}"))).collect(),
+        };
+
+        let mut results = Vec::with_capacity(repos.len());
+        for ((owner, repo), repository) in repos.iter().zip(graphql::parse_response(body, repos.len())) {
+            let data = match repository {
+                Ok(repository) => self.graphql_data(repository, owner, repo, options).await,
+                Err(e) => Err(e.context(format!("Failed to fetch {owner}/{repo}"))),
+            };
+            results.push(data);
+        }
+        results
+    }
+
+    async fn graphql_data(
+        &self,
+        mut repository: graphql::Repository,
+        owner: &str,
+        repo: &str,
+        options: &FetchOptions,
+    ) -> Result<RepositoryData> {
+        let now = options.now;
+        // The query has the default branch and permissions, CI and security don't fetch the repository again
+        let info = repository.info(owner, repo);
+        let (commits_total, last_commit) = repository.history(owner, repo)?;
+        let mut unsupported = Vec::new();
+        let mut errors = BTreeMap::new();
+        let (contributors, (activity, committers), triage, releases, ci, security, community, license, dependents) = tokio::join!(
+            self.get_contributors_count(owner, repo),
+            self.get_commit_history(owner, repo, now),
+            optional(options.triage, self.get_triage_activity(owner, repo, now)),
+            self.get_release_history(owner, repo, now),
+            optional(options.ci, self.get_ci_activity(owner, repo, &info)),
+            self.get_security(owner, repo, &info),
+            self.get_community(owner, repo),
+            self.get_license(owner, repo),
+            optional(options.dependents, self.get_dependents_count(owner, repo)),
+        );
+        let (pulls, issues) = triage.unzip();
+        Ok(RepositoryData {
+            last_commit,
+            commits_total: Some(commits_total),
+            contributors_total: metric(contributors, "contributors_total", &mut unsupported, &mut errors),
+            open_pull_requests: Some(repository.open_pull_requests()),
+            open_issues: Some(repository.open_issues()),
+            latest_release: repository.latest_release(),
+            commit_activity: metric(activity, "cadence", &mut unsupported, &mut errors),
+            contributor_activity: metric(committers, "bus_factor", &mut unsupported, &mut errors),
+            pull_request_activity: pulls.and_then(|pulls| metric(pulls, "pull_requests", &mut unsupported, &mut errors)),
+            issue_activity: issues.and_then(|issues| metric(issues, "issues", &mut unsupported, &mut errors)),
+            release_history: metric(releases, "releases", &mut unsupported, &mut errors),
+            ci_activity: ci.and_then(|ci| metric(ci, "ci", &mut unsupported, &mut errors)),
+            security: metric(security, "security", &mut unsupported, &mut errors),
+            community: metric(community, "community", &mut unsupported, &mut errors),
+            license: metric(license, "license", &mut unsupported, &mut errors),
+            popularity: Some(info.popularity),
+            dependents: dependents.and_then(|dependents| metric(dependents, "dependents", &mut unsupported, &mut errors)),
+            metadata: Some(info.metadata),
+            unsupported,
+            errors,
+        })
     }
 
-    pub async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
-        let url = format!("{BASE}/repos/{owner}/{repo}/commits?per_page=1");
-        let resp = self.client.get(&url).send().await
+    async fn fetch_count_via_link(&self, path_with_query: &str) -> Result<usize> {
+        let url = format!("{}{path_with_query}", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch data from {url}"))?
+            .error_for_status()
+            .with_context(|| format!("GitHub API error for endpoint: {path_with_query}"))?;
+
+        if let Some(link) = resp.headers().get(header::LINK) {
+            let link_str = link.to_str().unwrap_or_default();
+            if let Some(last_page) = parse_last_page(link_str) {
+                return Ok(last_page);
+            }
+            // if not `last`, there may be at least `next` → we know results are >= 2
+            if parse_rel_url(link_str, "next").is_some() {
+                return Ok(2); // at least 2 (conservative estimate)
+            }
+        }
+
+        // Without Link: count from body (0 or 1)
+        let text = resp.text()?;
+        let v: serde_json::Value = serde_json::from_str(&text).context("Invalid JSON response")?;
+        if let Some(arr) = v.as_array() {
+            return Ok(arr.len());
+        }
+        Ok(0)
+    }
+
+    /// Follows the `next` links from `url`, up to [`ACTIVITY_MAX_PAGES`] pages
+    /// and until `done` holds for a page
+    async fn list_pages<T: DeserializeOwned>(
+        &self,
+        url: String,
+        owner: &str,
+        repo: &str,
+        done: impl Fn(&[T]) -> bool,
+    ) -> Result<Vec<T>> {
+        let mut url = Some(url);
+        let mut items = Vec::new();
+        for _ in 0..ACTIVITY_MAX_PAGES {
+            let Some(page_url) = url.take() else { break };
+            let resp = self.http.send(self.http.get(&page_url)).await
+                .with_context(|| format!("Failed to fetch data from {page_url}"))?
+                .error_for_status()
+                .with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?;
+            let page: Vec<T> = resp.json()
+                .with_context(|| format!("Failed to parse response from {page_url}"))?;
+            if !done(&page) {
+                url = resp.headers().get(header::LINK)
+                    .and_then(|link| parse_rel_url(link.to_str().unwrap_or_default(), "next"));
+            }
+            items.extend(page);
+        }
+        Ok(items)
+    }
+
+    /// Fetches `url` and parses the JSON body
+    async fn get_json<T: DeserializeOwned>(&self, url: String, owner: &str, repo: &str) -> Result<T> {
+        self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch data from {url}"))?
+            .error_for_status()
+            .with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?
+            .json()
+            .with_context(|| format!("Failed to parse response from {url}"))
+    }
+
+    /// Whether `url` exists: enabled for 200 and 204, disabled for 404 and
+    /// unknown when the credentials aren't allowed to see it; rate limits fail
+    async fn probe(&self, url: String, owner: &str, repo: &str) -> Result<(SecurityState, HttpResponse)> {
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch data from {url}"))?;
+        let state = match resp.status().as_u16() {
+            200 | 204 => SecurityState::Enabled,
+            404 => SecurityState::Disabled,
+            401 | 403 if !resp.rate_limited() => SecurityState::Unknown,
+            status => {
+                resp.error_for_status().with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?;
+                anyhow::bail!("Unexpected status {status} from {url}")
+            }
+        };
+        Ok((state, resp))
+    }
+
+    /// Files in the root, `.github` and `docs` directories of the encoded `branch`, the
+    /// places of the security policy and Dependabot configuration, from one
+    /// tree listing per directory; `docs` is only listed without a policy
+    /// elsewhere. `None` when the credentials can't read the trees.
+    async fn list_config_files(&self, owner: &str, repo: &str, branch: &str) -> Result<Option<Vec<String>>> {
+        let base = format!("{}/repos/{owner}/{repo}/git/trees", self.base_url);
+        let root: TreeResp = match self.probe(format!("{base}/{branch}"), owner, repo).await? {
+            (SecurityState::Unknown, _) => return Ok(None),
+            (_, resp) => resp
+                .error_for_status()
+                .with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?
+                .json()
+                .context("Failed to parse tree response")?,
+        };
+        let mut files = Vec::new();
+        let mut dirs = HashMap::new();
+        for entry in root.tree {
+            match entry.kind.as_str() {
+                "blob" => files.push(entry.path),
+                "tree" => {
+                    dirs.insert(entry.path, entry.sha);
+                }
+                _ => {}
+            }
+        }
+        for dir in [".github", "docs"] {
+            let has_policy = SECURITY_POLICY_PATHS.iter().any(|path| files.iter().any(|file| file == path));
+            let Some(sha) = dirs.get(dir).filter(|_| dir != "docs" || !has_policy) else { continue };
+            let tree: TreeResp = self.get_json(format!("{base}/{sha}"), owner, repo).await?;
+            let blobs = tree.tree.into_iter().filter(|entry| entry.kind == "blob");
+            files.extend(blobs.map(|entry| format!("{dir}/{}", entry.path)));
+        }
+        Ok(Some(files))
+    }
+
+    /// Published security advisories, `None` when the token isn't allowed to list them
+    async fn list_advisories(&self, owner: &str, repo: &str) -> Result<Option<Vec<AdvisoryInfo>>> {
+        let url = format!("{}/repos/{owner}/{repo}/security-advisories?state=published&per_page=100", self.base_url);
+        let resp = match self.probe(url.clone(), owner, repo).await? {
+            (SecurityState::Unknown, _) => return Ok(None),
+            (_, resp) => resp
+                .error_for_status()
+                .with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?,
+        };
+        let mut advisories: Vec<AdvisoryResp> = resp.json()
+            .with_context(|| format!("Failed to parse response from {url}"))?;
+        let next = resp.headers().get(header::LINK)
+            .and_then(|link| parse_rel_url(link.to_str().unwrap_or_default(), "next"));
+        if let Some(next) = next {
+            advisories.extend(self.list_pages(next, owner, repo, |_: &[AdvisoryResp]| false).await?);
+        }
+        Ok(Some(advisories.iter().filter_map(AdvisoryResp::info).collect()))
+    }
+
+    /// Lists the `issues` or `pulls` (review) comments of the year before `now`, newest first
+    async fn list_recent_comments(
+        &self,
+        kind: &str,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<CommentResp>> {
+        let cutoff = now - Duration::days(ACTIVITY_DAYS);
+        let url = format!("{}/repos/{owner}/{repo}/{kind}/comments?sort=created&direction=desc&per_page=100", self.base_url);
+        self.list_pages(url, owner, repo, |page: &[CommentResp]| page.iter().any(|comment| comment.created_at < cutoff))
+            .await
+    }
+
+    /// Lists the commits of the year before `now`, whole days so the URL stays
+    /// the same during a day; the statistics endpoints answer 202 until they
+    /// are computed and only cover the year before the request
+    async fn list_recent_commits(&self, owner: &str, repo: &str, now: DateTime<Utc>) -> Result<Vec<CommitInfo>> {
+        let since = (now - Duration::days(ACTIVITY_DAYS)).format("%Y-%m-%dT00:00:00Z");
+        let until = now.format("%Y-%m-%dT23:59:59Z");
+        let url = format!("{}/repos/{owner}/{repo}/commits?since={since}&until={until}&per_page=100", self.base_url);
+        self.list_pages(url, owner, repo, |_| false).await
+    }
+
+    /// Commits per week of the 52 weeks before the request, `None` when they
+    /// aren't computed yet or start after the window ending at `now`
+    async fn commit_activity_stats(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<CommitActivity>> {
+        let url = format!("{}/repos/{owner}/{repo}/stats/commit_activity", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch commit activity from {url}"))?;
+
+        match resp.status().as_u16() {
+            // The statistics are computed in the background on the first request
+            202 => Ok(None),
+            // Empty repository
+            204 => Ok(Some(CommitActivity::default())),
+            _ => {
+                let resp = resp.error_for_status()
+                    .with_context(|| format!("Commit activity API error for repository {owner}/{repo}"))?;
+                let weeks: Vec<ActivityWeekResp> = resp.json()
+                    .context("Failed to parse commit activity response")?;
+                let start = (now - Duration::days(ACTIVITY_DAYS - 7)).timestamp();
+                Ok(weeks.first().is_none_or(|week| week.week <= start).then(|| weeks.into_iter().collect()))
+            }
+        }
+    }
+
+    /// Commits per contributor and week of the whole history, so they cover any
+    /// window; `None` when they aren't computed yet
+    async fn contributor_stats(&self, owner: &str, repo: &str) -> Result<Option<ContributorActivity>> {
+        let url = format!("{}/repos/{owner}/{repo}/stats/contributors", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch contributor statistics from {url}"))?;
+
+        match resp.status().as_u16() {
+            202 => Ok(None),
+            204 => Ok(Some(ContributorActivity::default())),
+            _ => {
+                let resp = resp.error_for_status()
+                    .with_context(|| format!("Contributor statistics API error for repository {owner}/{repo}"))?;
+                let contributors: Vec<ContributorStatsResp> = resp.json()
+                    .context("Failed to parse contributor statistics response")?;
+                Ok(Some(contributors.into_iter().collect()))
+            }
+        }
+    }
+}
+
+impl ForgeClient for GitHubClient {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    async fn fetch_repository(&self, owner: &str, repo: &str, options: &FetchOptions) -> Result<RepositoryData> {
+        match &self.graphql_url {
+            Some(url) => self.fetch_graphql(url, &[(owner, repo)], options).await.remove(0),
+            None => fetch_metrics(self, owner, repo, options).await,
+        }
+    }
+
+    fn batch_size(&self) -> usize {
+        if self.graphql_url.is_some() { GRAPHQL_BATCH_SIZE } else { 1 }
+    }
+
+    async fn fetch_repositories(&self, repos: &[(&str, &str)], options: &FetchOptions) -> Vec<Result<RepositoryData>> {
+        let Some(url) = &self.graphql_url else {
+            let mut results = Vec::with_capacity(repos.len());
+            for (owner, repo) in repos {
+                results.push(fetch_metrics(self, owner, repo, options).await);
+            }
+            return results;
+        };
+        let mut results = Vec::with_capacity(repos.len());
+        for chunk in repos.chunks(GRAPHQL_BATCH_SIZE) {
+            results.extend(self.fetch_graphql(url, chunk, options).await);
+        }
+        results
+    }
+
+    async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
+        let url = format!("{}/repos/{owner}/{repo}/commits?per_page=1", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
             .with_context(|| format!("Failed to fetch commits from {url}"))?
             .error_for_status()
             .with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?;
-        let mut items: Vec<CommitInfo> = resp.json().await
+        let mut items: Vec<CommitInfo> = resp.json()
             .context("Failed to parse commit response as JSON")?;
         items.pop().with_context(|| format!("Repository {owner}/{repo} has no commits"))
     }
 
-    pub async fn get_commit_count(&self, owner: &str, repo: &str) -> Result<usize> {
+    async fn get_commit_count(&self, owner: &str, repo: &str) -> Result<usize> {
         // Primary attempt: Link last
         let via_link = self.fetch_count_via_link(&format!("/repos/{owner}/{repo}/commits?per_page=1")).await?;
         if via_link > 1 {
@@ -49,39 +497,39 @@ impl GitHubClient {
         }
         
         // Fallback: Search API
-        let url = format!("{BASE}/search/commits?q=repo:{owner}/{repo}");
-        let resp = self.client.get(&url).send().await
+        let url = format!("{}/search/commits?q=repo:{owner}/{repo}", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
             .with_context(|| format!("Failed to search commits from {url}"))?
             .error_for_status()
             .with_context(|| format!("Search API error for repository {owner}/{repo}"))?;
-        let body: SearchCommitsResp = resp.json().await
+        let body: SearchCommitsResp = resp.json()
             .context("Failed to parse search commits response")?;
         Ok(body.total_count)
     }
 
-    pub async fn get_contributors_count(&self, owner: &str, repo: &str) -> Result<usize> {
+    async fn get_contributors_count(&self, owner: &str, repo: &str) -> Result<usize> {
         self.fetch_count_via_link(&format!("/repos/{owner}/{repo}/contributors?per_page=1&anon=1")).await
     }
 
-    pub async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
+    async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
         self.fetch_count_via_link(&format!("/repos/{owner}/{repo}/pulls?state=open&per_page=1")).await
     }
 
-    pub async fn get_open_issues_count(&self, owner: &str, repo: &str) -> Result<usize> {
+    async fn get_open_issues_count(&self, owner: &str, repo: &str) -> Result<usize> {
         let query = format!("q=is:issue+is:open+repo:{owner}/{repo}");
-        let url = format!("{BASE}/search/issues?{query}");
-        let resp = self.client.get(&url).send().await
+        let url = format!("{}/search/issues?{query}", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
             .with_context(|| format!("Failed to search issues from {url}"))?
             .error_for_status()
             .with_context(|| format!("Issues search API error for repository {owner}/{repo}"))?;
-        let body: SearchIssuesResp = resp.json().await
+        let body: SearchIssuesResp = resp.json()
             .context("Failed to parse search issues response")?;
         Ok(body.total_count)
     }
 
-    pub async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
-        let url = format!("{BASE}/repos/{owner}/{repo}/releases/latest");
-        let resp = self.client.get(&url).send().await
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
+        let url = format!("{}/repos/{owner}/{repo}/releases/latest", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
             .with_context(|| format!("Failed to fetch latest release from {url}"))?;
         
         // GitHub returns 404 if no releases exist
@@ -92,41 +540,322 @@ impl GitHubClient {
         let resp = resp.error_for_status()
             .with_context(|| format!("Latest release API error for repository {owner}/{repo}"))?;
         
-        let release: ReleaseInfo = resp.json().await
+        let release: ReleaseInfo = resp.json()
             .context("Failed to parse latest release response")?;
         
         Ok(Some(release))
     }
 
-    async fn fetch_count_via_link(&self, path_with_query: &str) -> Result<usize> {
-        let url = format!("{BASE}{path_with_query}");
-        let resp = self.client.get(&url).send().await
-            .with_context(|| format!("Failed to fetch data from {url}"))?
+    /// Releases of the year before `now` and the page before, or the newest
+    /// [`TAG_HISTORY_MAX`] version tags dated by their commits when the
+    /// repository never published a release
+    async fn get_release_history(&self, owner: &str, repo: &str, now: DateTime<Utc>) -> Result<ReleaseHistory> {
+        let cutoff = now - Duration::days(ACTIVITY_DAYS);
+        let url = format!("{}/repos/{owner}/{repo}/releases?per_page=100", self.base_url);
+        let releases: Vec<ReleaseInfo> = self
+            .list_pages(url, owner, repo, |page: &[ReleaseInfo]| {
+                page.iter().any(|release| release.published_at.is_some_and(|at| at < cutoff))
+            })
+            .await?;
+        if !releases.is_empty() {
+            return Ok(ReleaseHistory::from_releases(releases));
+        }
+
+        // Tags carry no date, only the newest versions are looked up
+        let url = format!("{}/repos/{owner}/{repo}/tags?per_page=100", self.base_url);
+        let mut tags: Vec<(Version, TagResp)> = self
+            .list_pages(url, owner, repo, |_: &[TagResp]| true)
+            .await?
+            .into_iter()
+            .filter_map(|tag| Some((Version::parse(&tag.name)?, tag)))
+            .collect();
+        tags.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+        let dated = tags.into_iter().take(TAG_HISTORY_MAX).map(|(_, tag)| async move {
+            let url = format!("{}/repos/{owner}/{repo}/commits/{}", self.base_url, tag.commit.sha);
+            let commit: CommitInfo = self.get_json(url, owner, repo).await?;
+            Ok::<_, anyhow::Error>((tag.name, commit.commit.author.date))
+        });
+        Ok(ReleaseHistory::from_tags(futures::future::try_join_all(dated).await?))
+    }
+
+    /// The commit activity and contributor statistics; the commits of the
+    /// year are listed once for both when they aren't computed yet or don't
+    /// cover the window
+    async fn get_commit_history(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<CommitActivity>, Result<ContributorActivity>) {
+        let (activity, committers) =
+            tokio::join!(self.commit_activity_stats(owner, repo, now), self.contributor_stats(owner, repo));
+        let listed = if matches!(activity, Ok(None)) || matches!(committers, Ok(None)) {
+            let commits = self.list_recent_commits(owner, repo, now).await.map(|commits| {
+                commits.into_iter().map(|item| (item.commit.author.email.to_lowercase(), item.commit.author.date))
+            });
+            Some(from_commits(commits))
+        } else {
+            None
+        };
+        let (listed_activity, listed_committers) = listed.unzip();
+        (
+            activity.and_then(|stats| {
+                stats.map_or_else(|| listed_activity.expect("listed without statistics"), Ok)
+            }),
+            committers.and_then(|stats| {
+                stats.map_or_else(|| listed_committers.expect("listed without statistics"), Ok)
+            }),
+        )
+    }
+
+    /// Pull requests and issues created in the year before `now` (closed
+    /// issues updated in it), and the comments and review comments of that
+    /// year (whole pages, so a few older ones too); comments by maintainers
+    /// other than the author count as responses. Issue comments cover pull
+    /// requests too, so they are listed once for both.
+    async fn get_triage_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<PullRequestActivity>, Result<IssueActivity>) {
+        // Newest first, stopping at the first page reaching past the year before `now`
+        let cutoff = now - Duration::days(ACTIVITY_DAYS);
+        let old_pulls = |page: &[PullResp]| page.iter().any(|pull| pull.created_at < cutoff);
+        let old_issues = |page: &[IssueResp]| page.iter().any(|issue| issue.created_at < cutoff);
+        let base = format!("{}/repos/{owner}/{repo}", self.base_url);
+        let newest = "sort=created&direction=desc&per_page=100";
+        let (open_pulls, closed_pulls, open_issues, closed_issues, comments, review_comments) = tokio::join!(
+            self.list_pages::<PullResp>(format!("{base}/pulls?state=open&{newest}"), owner, repo, old_pulls),
+            self.list_pages::<PullResp>(format!("{base}/pulls?state=closed&{newest}"), owner, repo, old_pulls),
+            self.list_pages::<IssueResp>(format!("{base}/issues?state=open&{newest}"), owner, repo, old_issues),
+            // Closing an issue updates it, so the recently closed ones are recently updated
+            self.list_pages::<IssueResp>(
+                format!("{base}/issues?state=closed&sort=updated&direction=desc&per_page=100"),
+                owner,
+                repo,
+                |page: &[IssueResp]| page.iter().any(|issue| issue.updated_at < cutoff),
+            ),
+            self.list_recent_comments("issues", owner, repo, now),
+            self.list_recent_comments("pulls", owner, repo, now),
+        );
+
+        let comments = comments.map_err(|e| format!("{e:#}"));
+        let comments = || comments.as_deref().map_err(|e| anyhow::anyhow!("{e}"));
+        let pulls = async {
+            let pulls = open_pulls?.into_iter().chain(closed_pulls?);
+            let review_comments = review_comments?;
+            Ok::<_, anyhow::Error>(pull_request_activity(pulls, comments()?.iter().chain(&review_comments)))
+        };
+        let issues = async {
+            let issues = open_issues?.into_iter().chain(closed_issues?);
+            Ok::<_, anyhow::Error>(issue_activity(issues, comments()?))
+        };
+        (pulls.await, issues.await)
+    }
+
+    /// Combined status and check runs of the default branch head, and the
+    /// latest finished workflow runs on the branch
+    async fn get_ci_activity(&self, owner: &str, repo: &str, repository: &RepositoryInfo) -> Result<CiActivity> {
+        let base = format!("{}/repos/{owner}/{repo}", self.base_url);
+        let branch = repository.metadata.default_branch.as_deref().context("Repository has no default branch")?;
+        let branch = encode_ref(branch);
+        let (status, checks, runs) = tokio::join!(
+            self.get_json::<CombinedStatusResp>(format!("{base}/commits/{branch}/status"), owner, repo),
+            self.get_json::<CheckRunsResp>(format!("{base}/commits/{branch}/check-runs?per_page=100"), owner, repo),
+            self.get_json::<WorkflowRunsResp>(
+                format!("{base}/actions/runs?branch={branch}&status=completed&per_page={CI_RUNS_MAX}"),
+                owner,
+                repo,
+            ),
+        );
+        let head = status?.combine(&checks?);
+        Ok(CiActivity::new(head, runs?.workflow_runs.iter().filter_map(WorkflowRunResp::run)))
+    }
+
+    /// Security policy file, published advisories, Dependabot configuration
+    /// and protection of the default branch; vulnerability alerts are only
+    /// visible to admins and unknown otherwise
+    async fn get_security(&self, owner: &str, repo: &str, repository: &RepositoryInfo) -> Result<SecurityData> {
+        let base = format!("{}/repos/{owner}/{repo}", self.base_url);
+        let branch = repository.metadata.default_branch.as_deref().context("Repository has no default branch")?;
+        let branch = encode_ref(branch);
+        let admin = repository.admin;
+        let (files, advisories, alerts, protection) = tokio::join!(
+            self.list_config_files(owner, repo, &branch),
+            self.list_advisories(owner, repo),
+            async {
+                if !admin {
+                    return Ok(SecurityState::Unknown);
+                }
+                self.probe(format!("{base}/vulnerability-alerts"), owner, repo).await.map(|(state, _)| state)
+            },
+            async {
+                match self.probe(format!("{base}/branches/{branch}"), owner, repo).await? {
+                    (SecurityState::Enabled, resp) => {
+                        let branch: BranchResp = resp.json().context("Failed to parse branch response")?;
+                        Ok(if branch.protected { SecurityState::Enabled } else { SecurityState::Disabled })
+                    }
+                    // The default branch exists, so a 404 means it is hidden too
+                    _ => Ok::<_, anyhow::Error>(SecurityState::Unknown),
+                }
+            },
+        );
+        let files = files?;
+        let present = |paths: &[&str]| match &files {
+            Some(files) if paths.iter().any(|path| files.iter().any(|file| file == path)) => SecurityState::Enabled,
+            Some(_) => SecurityState::Disabled,
+            None => SecurityState::Unknown,
+        };
+        Ok(SecurityData {
+            security_policy: present(&SECURITY_POLICY_PATHS),
+            advisories: advisories?,
+            vulnerability_alerts: alerts?,
+            dependabot_updates: present(&DEPENDABOT_CONFIG_PATHS),
+            branch_protection: protection?,
+        })
+    }
+
+    async fn get_community(&self, owner: &str, repo: &str) -> Result<CommunityProfile> {
+        let url = format!("{}/repos/{owner}/{repo}/community/profile", self.base_url);
+        let profile: CommunityProfileResp = self.get_json(url, owner, repo).await?;
+        Ok(profile.profile())
+    }
+
+    async fn get_license(&self, owner: &str, repo: &str) -> Result<License> {
+        let url = format!("{}/repos/{owner}/{repo}/license", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch license from {url}"))?;
+
+        // GitHub returns 404 if there is no license file
+        if resp.status() == 404 {
+            return Ok(License::none());
+        }
+
+        let resp = resp.error_for_status()
+            .with_context(|| format!("License API error for repository {owner}/{repo}"))?;
+        let license: LicenseFileResp = resp.json()
+            .context("Failed to parse license response")?;
+        Ok(license.license())
+    }
+
+    /// Repositories in the "Used by" count of the dependency graph; the API
+    /// doesn't have it, so it is read from the web page
+    async fn get_dependents_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        let url = format!("{}/{owner}/{repo}/network/dependents", web_url(&self.base_url));
+        let resp = self.web.send(self.web.get(&url).header(header::ACCEPT, "text/html")).await
+            .with_context(|| format!("Failed to fetch dependents from {url}"))?
             .error_for_status()
-            .with_context(|| format!("GitHub API error for endpoint: {path_with_query}"))?;
+            .with_context(|| format!("Dependency graph error for repository {owner}/{repo}"))?;
+        parse_dependents(&resp.text()?).with_context(|| format!("No dependents count in {url}"))
+    }
 
-        if let Some(link) = resp.headers().get(header::LINK) {
-            let link_str = link.to_str().unwrap_or_default();
-            if let Some(last_page) = parse_last_page(link_str) {
-                return Ok(last_page);
-            }
-            // if not `last`, there may be at least `next` → we know results are >= 2
-            if parse_rel_url(link_str, "next").is_some() {
-                return Ok(2); // at least 2 (conservative estimate)
-            }
+    async fn get_repository(&self, owner: &str, repo: &str) -> Result<RepositoryInfo> {
+        // Renamed and transferred repositories are redirected to their new location
+        let url = format!("{}/repos/{owner}/{repo}", self.base_url);
+        let resp = self.http.send(self.http.get(&url)).await
+            .with_context(|| format!("Failed to fetch repository from {url}"))?
+            .error_for_status()
+            .with_context(|| format!("GitHub API error for repository {owner}/{repo}"))?;
+        let repository: RepositoryResp = resp.json()
+            .context("Failed to parse repository response")?;
+        Ok(repository.info(owner, repo))
+    }
+}
+
+/// Pull requests with the responses among `comments`
+fn pull_request_activity<'a>(
+    pulls: impl IntoIterator<Item = PullResp>,
+    comments: impl IntoIterator<Item = &'a CommentResp>,
+) -> PullRequestActivity {
+    let mut activity = PullRequestActivity::default();
+    let mut authors = HashMap::new();
+    for pull in pulls {
+        if let Some(user) = pull.user {
+            authors.insert(pull.number, user.login);
         }
+        activity.insert(
+            pull.number,
+            PullRequestInfo {
+                created_at: pull.created_at,
+                closed_at: pull.closed_at,
+                merged_at: pull.merged_at,
+                first_response_at: None,
+                responded: false,
+            },
+        );
+    }
+    for (number, at) in comments.into_iter().filter_map(|comment| maintainer_response(comment, &authors)) {
+        activity.respond(number, at);
+    }
+    activity
+}
 
-        // Without Link: count from body (0 or 1)
-        let text = resp.text().await?;
-        let v: serde_json::Value = serde_json::from_str(&text).context("Invalid JSON response")?;
-        if let Some(arr) = v.as_array() {
-            return Ok(arr.len());
+/// Issues with the responses among `comments`; pull requests are left out
+fn issue_activity<'a>(
+    issues: impl IntoIterator<Item = IssueResp>,
+    comments: impl IntoIterator<Item = &'a CommentResp>,
+) -> IssueActivity {
+    let mut activity = IssueActivity::default();
+    let mut authors = HashMap::new();
+    for issue in issues.into_iter().filter(|issue| issue.pull_request.is_none()) {
+        activity.insert(issue.number, issue.info());
+        if let Some(user) = issue.user {
+            authors.insert(issue.number, user.login);
         }
-        Ok(0)
     }
+    for (number, at) in comments.into_iter().filter_map(|comment| maintainer_response(comment, &authors)) {
+        activity.respond(number, at);
+    }
+    activity
+}
+
+/// Number and time of a comment by a maintainer other than the author of
+/// the issue or pull request, `authors` by number
+fn maintainer_response(comment: &CommentResp, authors: &HashMap<u64, String>) -> Option<(u64, DateTime<Utc>)> {
+    let number = comment.number()?;
+    let by_author = comment.user.as_ref().map(|user| &user.login) == authors.get(&number);
+    (comment.by_maintainer() && !by_author).then_some((number, comment.created_at))
 }
 
-fn parse_last_page(link_header: &str) -> Option<usize> {
+/// GraphQL endpoint for a REST API root: `https://api.github.com/graphql`, or
+/// `https://host/api/graphql` for GitHub Enterprise
+fn graphql_url(base_url: &str) -> String {
+    match base_url.strip_suffix("/api/v3") {
+        Some(host) => format!("{host}/api/graphql"),
+        None => format!("{base_url}/graphql"),
+    }
+}
+
+/// Web root for a REST API root: `https://github.com`, or `https://host` for GitHub Enterprise
+fn web_url(base_url: &str) -> String {
+    match base_url.strip_suffix("/api/v3") {
+        Some(host) => host.to_string(),
+        None => base_url.replacen("://api.", "://", 1),
+    }
+}
+
+/// Branch or tag name percent-encoded for a URL path segment or query value,
+/// so `feature/x` or `a&b` stay one ref
+fn encode_ref(name: &str) -> String {
+    name.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Dependent repositories on a dependency graph page, from the link that
+/// switches to them: `<a ... href="...?dependent_type=REPOSITORY"> <svg/> 1,234 Repositories </a>`
+fn parse_dependents(html: &str) -> Option<usize> {
+    let link = &html[html.find("dependent_type=REPOSITORY")?..];
+    let text = &link[..link.find("Repositories")?];
+    let count = text.rsplit('>').next()?.trim().replace(',', "");
+    count.parse().ok()
+}
+
+pub(crate) fn parse_last_page(link_header: &str) -> Option<usize> {
     // Look for the segment with rel="last", extract page=
     for part in link_header.split(',') {
         let part = part.trim();
@@ -141,10 +870,8 @@ fn parse_last_page(link_header: &str) -> Option<usize> {
                 let mut it = kv.splitn(2, '=');
                 let k = it.next()?;
                 let v = it.next().unwrap_or("");
-                if k == "page" {
-                    if let Ok(n) = v.parse::<usize>() {
-                        return Some(n);
-                    }
+                if k == "page" && let Ok(n) = v.parse::<usize>() {
+                    return Some(n);
                 }
             }
         }
@@ -163,4 +890,46 @@ fn parse_rel_url(link_header: &str, rel: &str) -> Option<String> {
         }
     }
     None
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_url() {
+        assert_eq!(graphql_url("https://api.github.com"), "https://api.github.com/graphql");
+        assert_eq!(graphql_url("https://github.example.com/api/v3"), "https://github.example.com/api/graphql");
+    }
+
+    #[test]
+    fn test_encode_ref() {
+        assert_eq!(encode_ref("main"), "main");
+        assert_eq!(encode_ref("release/1.x"), "release%2F1.x");
+        assert_eq!(encode_ref("a&b=c#d?"), "a%26b%3Dc%23d%3F");
+        assert_eq!(encode_ref("fix ü"), "fix%20%C3%BC");
+    }
+
+    #[test]
+    fn test_dependents_page() {
+        assert_eq!(web_url("https://api.github.com"), "https://github.com");
+        assert_eq!(web_url("https://github.example.com/api/v3"), "https://github.example.com");
+
+        let html = r#"<a class="btn-link selected" href="/o/r/network/dependents?dependent_type=REPOSITORY&amp;package_id=UGFj">
+            <svg aria-hidden="true" height="16"><path d="M2 2.5"></path></svg>
+            12,345
+            Repositories
+          </a>
+          <a class="btn-link" href="/o/r/network/dependents?dependent_type=PACKAGE"> 67 Packages </a>"#;
+        assert_eq!(parse_dependents(html), Some(12_345));
+        assert_eq!(parse_dependents("<p>The dependency graph is not enabled</p>"), None);
+    }
+
+    #[test]
+    fn test_graphql_requires_token() {
+        assert!(GitHubClient::new(None).unwrap().graphql_url.is_none());
+        assert!(GitHubClient::new(Some("token")).unwrap().graphql_url.is_some());
+        assert!(GitHubClient::builder().token("token").rest_only().build().unwrap().graphql_url.is_none());
+        assert_eq!(GitHubClient::new(Some("token")).unwrap().batch_size(), GRAPHQL_BATCH_SIZE);
+    }
+}
@@ -0,0 +1,329 @@
+//! GraphQL v4 data path: one query per chunk of repositories, each repository
+//! aliased as `r0`, `r1`, ... so a batch costs a single request.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::report::{Popularity, RepositoryMetadata};
+use crate::types::{AuthorMeta, CommitInfo, CommitMeta, ReleaseInfo, RepositoryInfo};
+
+const REPOSITORY_FRAGMENT: &str = "
+fragment Activity on Repository {
+  nameWithOwner
+  isArchived
+  isFork
+  isDisabled
+  isTemplate
+  pushedAt
+  stargazerCount
+  forkCount
+  watchers { totalCount }
+  parent { nameWithOwner }
+  viewerPermission
+  defaultBranchRef {
+    name
+    target {
+      ... on Commit {
+        history(first: 1) {
+          totalCount
+          nodes { oid message author { name email date } }
+        }
+      }
+    }
+  }
+  pullRequests(states: OPEN) { totalCount }
+  issues(states: OPEN) { totalCount }
+  latestRelease { tagName name publishedAt isPrerelease isDraft }
+}";
+
+/// Request body querying all `repos` at once
+pub(super) fn query(repos: &[(&str, &str)]) -> Value {
+    let mut params = Vec::new();
+    let mut fields = Vec::new();
+    let mut variables = serde_json::Map::new();
+    for (i, (owner, repo)) in repos.iter().enumerate() {
+        params.push(format!("$o{i}: String!, $n{i}: String!"));
+        fields.push(format!("  r{i}: repository(owner: $o{i}, name: $n{i}) {{ ...Activity }}"));
+        variables.insert(format!("o{i}"), json!(owner));
+        variables.insert(format!("n{i}"), json!(repo));
+    }
+    let query = format!("query({}) {{\n{}\n}}\n{REPOSITORY_FRAGMENT}", params.join(", "), fields.join("\n"));
+    json!({ "query": query, "variables": variables })
+}
+
+/// Repository fields returned by the query
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Repository {
+    name_with_owner: String,
+    is_archived: bool,
+    is_fork: bool,
+    is_disabled: bool,
+    is_template: bool,
+    pushed_at: Option<DateTime<Utc>>,
+    stargazer_count: usize,
+    fork_count: usize,
+    watchers: Connection,
+    parent: Option<Parent>,
+    /// `ADMIN`, `MAINTAIN`, `WRITE`, `TRIAGE` or `READ`, absent without access
+    viewer_permission: Option<String>,
+    default_branch_ref: Option<BranchRef>,
+    pull_requests: Connection,
+    issues: Connection,
+    latest_release: Option<Release>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Parent {
+    name_with_owner: String,
+}
+
+#[derive(Deserialize)]
+struct BranchRef {
+    name: String,
+    target: Option<Target>,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    history: History,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct History {
+    total_count: usize,
+    nodes: Vec<Commit>,
+}
+
+#[derive(Deserialize)]
+struct Commit {
+    oid: String,
+    message: String,
+    author: Option<Author>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    name: Option<String>,
+    email: Option<String>,
+    date: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection {
+    total_count: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Release {
+    tag_name: String,
+    name: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    is_prerelease: bool,
+    is_draft: bool,
+}
+
+impl Repository {
+    /// Properties of the repository queried as `owner/repo`
+    pub(super) fn metadata(&self, owner: &str, repo: &str) -> RepositoryMetadata {
+        RepositoryMetadata {
+            archived: self.is_archived,
+            fork: self.is_fork,
+            disabled: self.is_disabled,
+            parent: self.parent.as_ref().map(|parent| parent.name_with_owner.clone()),
+            is_template: self.is_template,
+            default_branch: self.default_branch_ref.as_ref().map(|branch| branch.name.clone()),
+            pushed_at: self.pushed_at,
+            moved_to: RepositoryMetadata::moved_to(owner, repo, &self.name_with_owner),
+        }
+    }
+
+    /// Metadata, popularity and access of the repository queried as `owner/repo`
+    pub(super) fn info(&self, owner: &str, repo: &str) -> RepositoryInfo {
+        RepositoryInfo {
+            metadata: self.metadata(owner, repo),
+            popularity: self.popularity(),
+            admin: self.viewer_permission.as_deref() == Some("ADMIN"),
+        }
+    }
+
+    /// Stars, forks and watchers; dependents aren't in the GraphQL API
+    pub(super) fn popularity(&self) -> Popularity {
+        Popularity {
+            stars: self.stargazer_count,
+            forks: self.fork_count,
+            watchers: Some(self.watchers.total_count),
+            ..Default::default()
+        }
+    }
+
+    /// Commit count and latest commit of the default branch
+    pub(super) fn history(&mut self, owner: &str, repo: &str) -> Result<(usize, CommitInfo)> {
+        let history = self
+            .default_branch_ref
+            .take()
+            .and_then(|branch| branch.target)
+            .map(|target| target.history);
+        let (total_count, commit) = history
+            .and_then(|h| Some(h.total_count).zip(h.nodes.into_iter().next()))
+            .with_context(|| format!("Repository {owner}/{repo} has no commits"))?;
+        let author = commit.author.unwrap_or(Author { name: None, email: None, date: None });
+        let date = author.date.with_context(|| format!("Last commit of {owner}/{repo} has no date"))?;
+        Ok((
+            total_count,
+            CommitInfo {
+                sha: commit.oid,
+                commit: CommitMeta {
+                    author: AuthorMeta {
+                        name: author.name.unwrap_or_default(),
+                        email: author.email.unwrap_or_default(),
+                        date,
+                    },
+                    message: commit.message,
+                },
+            },
+        ))
+    }
+
+    pub(super) fn open_pull_requests(&self) -> usize {
+        self.pull_requests.total_count
+    }
+
+    pub(super) fn open_issues(&self) -> usize {
+        self.issues.total_count
+    }
+
+    pub(super) fn latest_release(&mut self) -> Option<ReleaseInfo> {
+        self.latest_release.take().map(|release| ReleaseInfo {
+            tag_name: release.tag_name,
+            name: release.name,
+            published_at: release.published_at,
+            prerelease: release.is_prerelease,
+            draft: release.is_draft,
+        })
+    }
+}
+
+/// Splits a response into one result per queried repository. A repository
+/// that can't be resolved fails with the error GraphQL reported for its alias.
+pub(super) fn parse_response(mut body: Value, count: usize) -> Vec<Result<Repository>> {
+    let errors = body["errors"].as_array().cloned().unwrap_or_default();
+    let error_for = |alias: &str| {
+        let message = errors
+            .iter()
+            .find(|e| e["path"].get(0).and_then(Value::as_str) == Some(alias))
+            .or_else(|| errors.iter().find(|e| e.get("path").is_none()))
+            .and_then(|e| e["message"].as_str())
+            .unwrap_or("no data returned");
+        anyhow!("GitHub GraphQL API error: {message}")
+    };
+
+    (0..count)
+        .map(|i| {
+            let alias = format!("r{i}");
+            match body["data"].get_mut(&alias).map(Value::take) {
+                Some(Value::Null) | None => Err(error_for(&alias)),
+                Some(value) => serde_json::from_value(value).context("Failed to parse GraphQL repository"),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_aliases_repositories() {
+        let body = query(&[("rust-lang", "rust"), ("serde-rs", "serde")]);
+        let query = body["query"].as_str().unwrap();
+        assert!(query.contains("r0: repository(owner: $o0, name: $n0)"));
+        assert!(query.contains("r1: repository(owner: $o1, name: $n1)"));
+        assert!(query.contains("fragment Activity on Repository"));
+        assert_eq!(body["variables"]["o1"], "serde-rs");
+        assert_eq!(body["variables"]["n0"], "rust");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "data": {
+                "r0": {
+                    "nameWithOwner": "new-owner/repo",
+                    "isArchived": true,
+                    "isFork": true,
+                    "isDisabled": false,
+                    "isTemplate": false,
+                    "pushedAt": "2024-03-02T00:00:00Z",
+                    "stargazerCount": 1500, "forkCount": 120, "watchers": { "totalCount": 40 },
+                    "parent": { "nameWithOwner": "upstream/repo" },
+                    "viewerPermission": "ADMIN",
+                    "defaultBranchRef": { "name": "main", "target": { "history": {
+                        "totalCount": 1234,
+                        "nodes": [{
+                            "oid": "abc123",
+                            "message": "Fix bug\n\nDetails",
+                            "author": { "name": "Alice", "email": "alice@example.com", "date": "2024-03-01T11:00:00+01:00" }
+                        }]
+                    } } },
+                    "pullRequests": { "totalCount": 5 },
+                    "issues": { "totalCount": 7 },
+                    "latestRelease": {
+                        "tagName": "v1.0.0", "name": null, "publishedAt": "2024-02-01T00:00:00Z",
+                        "isPrerelease": false, "isDraft": false
+                    }
+                },
+                "r1": null
+            },
+            "errors": [{
+                "type": "NOT_FOUND",
+                "path": ["r1"],
+                "message": "Could not resolve to a Repository with the name 'test/missing'."
+            }]
+        });
+
+        let mut results = parse_response(body, 2);
+        let err = results.pop().unwrap().err().unwrap();
+        assert!(err.to_string().contains("Could not resolve to a Repository"));
+
+        let mut repository = results.pop().unwrap().unwrap();
+        let metadata = repository.metadata("test", "repo");
+        assert!(metadata.archived && metadata.fork && !metadata.disabled);
+        assert_eq!(metadata.parent.as_deref(), Some("upstream/repo"));
+        assert_eq!(metadata.default_branch.as_deref(), Some("main"));
+        assert_eq!(metadata.moved_to.as_deref(), Some("new-owner/repo"));
+        assert_eq!(repository.metadata("New-Owner", "repo").moved_to, None);
+        assert!(repository.info("test", "repo").admin);
+        let (commits, commit) = repository.history("test", "repo").unwrap();
+        assert_eq!(commits, 1234);
+        assert_eq!(commit.sha, "abc123");
+        assert_eq!(commit.commit.author.date.to_rfc3339(), "2024-03-01T10:00:00+00:00");
+        assert_eq!((repository.open_pull_requests(), repository.open_issues()), (5, 7));
+        assert_eq!(repository.latest_release().unwrap().tag_name, "v1.0.0");
+        let popularity = repository.popularity();
+        assert_eq!((popularity.stars, popularity.forks, popularity.watchers), (1500, 120, Some(40)));
+    }
+
+    #[test]
+    fn test_empty_repository() {
+        let body = json!({ "data": { "r0": {
+            "nameWithOwner": "test/empty",
+            "isArchived": false, "isFork": false, "isDisabled": false, "isTemplate": false,
+            "pushedAt": null, "parent": null,
+            "stargazerCount": 0, "forkCount": 0, "watchers": { "totalCount": 0 },
+            "defaultBranchRef": null,
+            "pullRequests": { "totalCount": 0 },
+            "issues": { "totalCount": 0 },
+            "latestRelease": null
+        } } });
+        let mut repository = parse_response(body, 1).pop().unwrap().unwrap();
+        assert_eq!(repository.metadata("test", "empty"), RepositoryMetadata::default());
+        assert!(repository.history("test", "empty").is_err());
+    }
+}
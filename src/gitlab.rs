@@ -0,0 +1,492 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Client, header};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::forge::{ForgeClient, Unsupported, from_commits};
+use crate::github::parse_last_page;
+use crate::http::{Cassette, DEFAULT_CONCURRENCY, HttpResponse, ResponseCache, Transport};
+use crate::report::{Popularity, RepositoryMetadata};
+use crate::types::{
+    ACTIVITY_DAYS, ACTIVITY_MAX_PAGES, AuthorMeta, CommitActivity, CommitInfo, CommitMeta, ContributorActivity,
+    IssueActivity, IssueInfo, PullRequestActivity, PullRequestInfo, ReleaseHistory, ReleaseInfo, RepositoryInfo,
+};
+
+static DEFAULT_BASE_URL: &str = "https://gitlab.com/api/v4";
+static DEFAULT_USER_AGENT: &str = "github-activity-check/0.1";
+
+/// Client for the GitLab REST API v4 (gitlab.com or self-hosted)
+pub struct GitLabClient {
+    http: Transport,
+    base_url: String,
+}
+
+/// Builder for [`GitLabClient`]
+#[derive(Default)]
+pub struct GitLabClientBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    concurrency: Option<usize>,
+    cache: Option<ResponseCache>,
+    cassette: Option<Cassette>,
+}
+
+impl GitLabClientBuilder {
+    /// Personal or project access token, sent as `PRIVATE-TOKEN`
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// API root, e.g. `https://gitlab.example.com/api/v4` (default: `https://gitlab.com/api/v4`)
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Maximum number of requests in flight (default: 4)
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Caches responses on disk and revalidates them with conditional requests
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Records every exchange into the cassette, or serves them from it
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    pub fn build(self) -> Result<GitLabClient> {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::USER_AGENT, header::HeaderValue::from_static(DEFAULT_USER_AGENT));
+        if let Some(t) = &self.token {
+            headers.insert("PRIVATE-TOKEN", header::HeaderValue::from_str(t)?);
+        }
+        let client = Client::builder().default_headers(headers).build()?;
+        let http = Transport::new(client, self.concurrency.unwrap_or(DEFAULT_CONCURRENCY))
+            .with_cache(self.cache.clone(), self.token.as_deref())
+            .with_cassette(self.cassette.clone());
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/').to_string();
+        Ok(GitLabClient { http, base_url })
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabCommit {
+    id: String,
+    author_name: String,
+    author_email: String,
+    authored_date: DateTime<Utc>,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabMergeRequest {
+    iid: u64,
+    created_at: DateTime<Utc>,
+    merged_at: Option<DateTime<Utc>>,
+    closed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    user_notes_count: usize,
+}
+
+impl GitLabMergeRequest {
+    /// Any comment counts as a response, GitLab lists only their number
+    fn info(&self) -> PullRequestInfo {
+        PullRequestInfo {
+            created_at: self.created_at,
+            // `closed_at` is only set for merge requests closed without merging
+            closed_at: self.merged_at.or(self.closed_at),
+            merged_at: self.merged_at,
+            first_response_at: None,
+            responded: self.user_notes_count > 0,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    created_at: DateTime<Utc>,
+    closed_at: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+impl GitLabIssue {
+    /// Comments aren't listed with issues, so response times are unknown
+    fn info(&self) -> IssueInfo {
+        IssueInfo {
+            created_at: self.created_at,
+            closed_at: self.closed_at,
+            updated_at: self.updated_at,
+            labeled: !self.labels.is_empty(),
+            first_response_at: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabProject {
+    path_with_namespace: String,
+    #[serde(default)]
+    archived: bool,
+    default_branch: Option<String>,
+    forked_from_project: Option<GitLabParent>,
+    statistics: Option<GitLabStatistics>,
+    #[serde(default)]
+    star_count: usize,
+    #[serde(default)]
+    forks_count: usize,
+}
+
+#[derive(Deserialize)]
+struct GitLabParent {
+    path_with_namespace: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabStatistics {
+    commit_count: usize,
+}
+
+#[derive(Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+    name: Option<String>,
+    released_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    upcoming_release: bool,
+}
+
+impl GitLabRelease {
+    fn info(self) -> ReleaseInfo {
+        ReleaseInfo {
+            tag_name: self.tag_name,
+            name: self.name,
+            published_at: self.released_at,
+            prerelease: self.upcoming_release,
+            draft: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabTag {
+    name: String,
+    commit: GitLabTagCommit,
+}
+
+#[derive(Deserialize)]
+struct GitLabTagCommit {
+    created_at: DateTime<Utc>,
+}
+
+impl GitLabProject {
+    fn metadata(self, owner: &str, repo: &str) -> RepositoryMetadata {
+        RepositoryMetadata {
+            archived: self.archived,
+            fork: self.forked_from_project.is_some(),
+            parent: self.forked_from_project.map(|parent| parent.path_with_namespace),
+            default_branch: self.default_branch,
+            moved_to: RepositoryMetadata::moved_to(owner, repo, &self.path_with_namespace),
+            ..Default::default()
+        }
+    }
+
+    /// Stars and forks; GitLab doesn't count watchers
+    fn popularity(&self) -> Popularity {
+        Popularity { stars: self.star_count, forks: self.forks_count, ..Default::default() }
+    }
+}
+
+impl GitLabClient {
+    pub fn builder() -> GitLabClientBuilder {
+        GitLabClientBuilder::default()
+    }
+
+    fn project_url(&self, owner: &str, repo: &str) -> String {
+        // Project paths are passed URL-encoded in place of the numeric id
+        let id = format!("{owner}/{repo}").replace('/', "%2F");
+        format!("{}/projects/{id}", self.base_url)
+    }
+
+    async fn get(&self, url: &str, owner: &str, repo: &str) -> Result<HttpResponse> {
+        self.http.send(self.http.get(url)).await
+            .with_context(|| format!("Failed to fetch data from {url}"))?
+            .error_for_status()
+            .with_context(|| format!("GitLab API error for project {owner}/{repo}"))
+    }
+
+    /// Reads the total from the `X-Total` header, falling back to the `Link` header
+    async fn fetch_total(&self, url: &str, owner: &str, repo: &str) -> Result<Option<usize>> {
+        let resp = self.get(url, owner, repo).await?;
+        let headers = resp.headers();
+
+        if let Some(total) = headers.get("x-total").and_then(|v| v.to_str().ok()).and_then(|v| v.parse().ok()) {
+            return Ok(Some(total));
+        }
+        if let Some(last_page) = headers
+            .get(header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_last_page)
+        {
+            return Ok(Some(last_page));
+        }
+        // GitLab omits totals for large collections (over 10,000 items)
+        if headers.get("x-next-page").is_some_and(|v| !v.is_empty()) {
+            return Ok(None);
+        }
+
+        let items: Vec<serde_json::Value> = resp.json().context("Invalid JSON response")?;
+        Ok(Some(items.len()))
+    }
+
+    /// Follows the `X-Next-Page` header from `url`, up to [`ACTIVITY_MAX_PAGES`]
+    /// pages and until `done` holds for a page
+    async fn list_pages<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        owner: &str,
+        repo: &str,
+        done: impl Fn(&[T]) -> bool,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut page = Some("1".to_string());
+        for _ in 0..ACTIVITY_MAX_PAGES {
+            let Some(current) = page.take() else { break };
+            let resp = self.get(&format!("{url}&page={current}"), owner, repo).await?;
+            let batch: Vec<T> = resp.json().with_context(|| format!("Failed to parse response from {url}"))?;
+            if !done(&batch) {
+                page = resp.headers().get("x-next-page")
+                    .and_then(|v| v.to_str().ok())
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string);
+            }
+            items.extend(batch);
+        }
+        Ok(items)
+    }
+
+    /// Lists the commits of the year before `now`, whole days so the URL stays the same during a day
+    async fn list_recent_commits(&self, owner: &str, repo: &str, now: DateTime<Utc>) -> Result<Vec<GitLabCommit>> {
+        let since = (now - Duration::days(ACTIVITY_DAYS)).format("%Y-%m-%dT00:00:00Z");
+        let until = now.format("%Y-%m-%dT23:59:59Z");
+        let url = format!(
+            "{}/repository/commits?since={since}&until={until}&per_page=100",
+            self.project_url(owner, repo)
+        );
+        self.list_pages(&url, owner, repo, |_| false).await
+    }
+
+    async fn fetch_count(&self, url: &str, owner: &str, repo: &str) -> Result<usize> {
+        self.fetch_total(url, owner, repo)
+            .await?
+            .with_context(|| format!("GitLab did not report a total for {url}"))
+    }
+}
+
+impl ForgeClient for GitLabClient {
+    fn name(&self) -> &'static str {
+        "GitLab"
+    }
+
+    async fn get_last_commit(&self, owner: &str, repo: &str) -> Result<CommitInfo> {
+        let url = format!("{}/repository/commits?per_page=1", self.project_url(owner, repo));
+        let mut items: Vec<GitLabCommit> = self.get(&url, owner, repo).await?.json()
+            .context("Failed to parse commit response as JSON")?;
+        let commit = items.pop().with_context(|| format!("Project {owner}/{repo} has no commits"))?;
+        Ok(CommitInfo {
+            sha: commit.id,
+            commit: CommitMeta {
+                author: AuthorMeta {
+                    name: commit.author_name,
+                    email: commit.author_email,
+                    date: commit.authored_date,
+                },
+                message: commit.message,
+            },
+        })
+    }
+
+    async fn get_commit_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        // Primary attempt: project statistics (not always visible without a token)
+        let url = format!("{}?statistics=true", self.project_url(owner, repo));
+        let project: GitLabProject = self.get(&url, owner, repo).await?.json()
+            .context("Failed to parse project response")?;
+        if let Some(statistics) = project.statistics {
+            return Ok(statistics.commit_count);
+        }
+
+        // Fallback: pagination headers of the commit listing, which GitLab
+        // leaves out for more than 10,000 commits
+        let url = format!("{}/repository/commits?per_page=1", self.project_url(owner, repo));
+        self.fetch_total(&url, owner, repo).await?.ok_or_else(|| {
+            Unsupported { forge: "GitLab", metric: "Commit count over 10,000 without project statistics" }.into()
+        })
+    }
+
+    async fn get_contributors_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        let url = format!("{}/repository/contributors?per_page=1", self.project_url(owner, repo));
+        self.fetch_count(&url, owner, repo).await
+    }
+
+    async fn get_open_prs_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        let url = format!("{}/merge_requests?state=opened&per_page=1", self.project_url(owner, repo));
+        self.fetch_count(&url, owner, repo).await
+    }
+
+    async fn get_open_issues_count(&self, owner: &str, repo: &str) -> Result<usize> {
+        let url = format!("{}/issues?state=opened&per_page=1", self.project_url(owner, repo));
+        self.fetch_count(&url, owner, repo).await
+    }
+
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<ReleaseInfo>> {
+        // Releases are sorted by released_at, newest first
+        let url = format!("{}/releases?per_page=1", self.project_url(owner, repo));
+        let mut items: Vec<GitLabRelease> = self.get(&url, owner, repo).await?.json()
+            .context("Failed to parse releases response")?;
+        Ok(items.pop().map(GitLabRelease::info))
+    }
+
+    /// Both from one listing of the commits of the year
+    async fn get_commit_history(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<CommitActivity>, Result<ContributorActivity>) {
+        let commits = self.list_recent_commits(owner, repo, now).await;
+        from_commits(commits.map(|commits| {
+            commits.into_iter().map(|commit| (commit.author_email.to_lowercase(), commit.authored_date))
+        }))
+    }
+
+    /// Releases of the year before `now` and the page before, or the tags with
+    /// their commit dates when the project never published a release
+    async fn get_release_history(&self, owner: &str, repo: &str, now: DateTime<Utc>) -> Result<ReleaseHistory> {
+        let cutoff = now - Duration::days(ACTIVITY_DAYS);
+        let url = format!("{}/releases?per_page=100", self.project_url(owner, repo));
+        let releases: Vec<GitLabRelease> = self
+            .list_pages(&url, owner, repo, |page: &[GitLabRelease]| {
+                page.iter().any(|release| release.released_at.is_some_and(|at| at < cutoff))
+            })
+            .await?;
+        if !releases.is_empty() {
+            return Ok(ReleaseHistory::from_releases(releases.into_iter().map(GitLabRelease::info)));
+        }
+
+        let url = format!("{}/repository/tags?order_by=updated&sort=desc&per_page=100", self.project_url(owner, repo));
+        let tags: Vec<GitLabTag> = self.list_pages(&url, owner, repo, |_| true).await?;
+        Ok(ReleaseHistory::from_tags(tags.into_iter().map(|tag| (tag.name, tag.commit.created_at))))
+    }
+
+    /// Merge requests and open issues created in the year before `now`, and
+    /// the closed issues updated in it; response times are unknown, an item
+    /// with comments counts as answered
+    async fn get_triage_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<PullRequestActivity>, Result<IssueActivity>) {
+        // Newest first, stopping at the first page reaching past the year before `now`
+        let cutoff = now - Duration::days(ACTIVITY_DAYS);
+        let project_url = self.project_url(owner, repo);
+        let newest = "order_by=created_at&sort=desc&per_page=100";
+        let merge_requests_url = format!("{project_url}/merge_requests?state=all&{newest}");
+        let open_url = format!("{project_url}/issues?state=opened&{newest}");
+        let closed_url = format!("{project_url}/issues?state=closed&order_by=updated_at&sort=desc&per_page=100");
+        let (merge_requests, open, closed) = tokio::join!(
+            self.list_pages::<GitLabMergeRequest>(&merge_requests_url, owner, repo, |page| {
+                page.iter().any(|merge_request| merge_request.created_at < cutoff)
+            }),
+            self.list_pages::<GitLabIssue>(&open_url, owner, repo, |page| {
+                page.iter().any(|issue| issue.created_at < cutoff)
+            }),
+            self.list_pages::<GitLabIssue>(&closed_url, owner, repo, |page| {
+                page.iter().any(|issue| issue.updated_at < cutoff)
+            }),
+        );
+
+        let pulls = merge_requests.map(|merge_requests| {
+            let mut activity = PullRequestActivity::default();
+            for merge_request in merge_requests {
+                activity.insert(merge_request.iid, merge_request.info());
+            }
+            activity
+        });
+        let issues = open.and_then(|open| {
+            let mut activity = IssueActivity::default();
+            for issue in open.into_iter().chain(closed?) {
+                activity.insert(issue.iid, issue.info());
+            }
+            Ok(activity)
+        });
+        (pulls, issues)
+    }
+
+    async fn get_repository(&self, owner: &str, repo: &str) -> Result<RepositoryInfo> {
+        // Renamed and transferred projects are redirected to their new path
+        let project: GitLabProject = self.get(&self.project_url(owner, repo), owner, repo).await?.json()
+            .context("Failed to parse project response")?;
+        Ok(RepositoryInfo { popularity: project.popularity(), metadata: project.metadata(owner, repo), admin: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_url_encodes_nested_groups() {
+        let client = GitLabClient::builder().base_url("https://gitlab.example.com/api/v4/").build().unwrap();
+        assert_eq!(
+            client.project_url("group/sub", "project"),
+            "https://gitlab.example.com/api/v4/projects/group%2Fsub%2Fproject"
+        );
+    }
+
+    #[test]
+    fn test_project_metadata() {
+        let project: GitLabProject = serde_json::from_value(serde_json::json!({
+            "path_with_namespace": "group/sub/renamed",
+            "archived": true,
+            "default_branch": "main",
+            "forked_from_project": { "path_with_namespace": "upstream/project" },
+            "star_count": 310,
+            "forks_count": 42,
+        }))
+        .unwrap();
+        let popularity = project.popularity();
+        assert_eq!((popularity.stars, popularity.forks, popularity.watchers), (310, 42, None));
+        let metadata = project.metadata("group/sub", "project");
+        assert!(metadata.archived && metadata.fork);
+        assert_eq!(metadata.parent.as_deref(), Some("upstream/project"));
+        assert_eq!(metadata.moved_to.as_deref(), Some("group/sub/renamed"));
+    }
+
+    #[test]
+    fn test_merge_request_info() {
+        let merged: GitLabMergeRequest = serde_json::from_value(serde_json::json!({
+            "iid": 12,
+            "created_at": "2024-05-01T10:00:00Z",
+            "merged_at": "2024-05-02T10:00:00Z",
+            "closed_at": null,
+            "user_notes_count": 0,
+        }))
+        .unwrap();
+        let info = merged.info();
+        // Merged merge requests have no `closed_at`
+        assert_eq!(info.closed_at, info.merged_at);
+        assert!(info.closed_at.is_some() && !info.responded);
+    }
+}
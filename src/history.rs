@@ -4,8 +4,11 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
-use crate::output::RepositoryReport;
+use crate::config::Config;
+use crate::report::RepositoryReport;
+use crate::scoring::ProjectScorer;
 
+/// Data of the previous run, used to detect changes with `--check`
 #[derive(Serialize, Deserialize, Debug)]
 pub struct HistoryData {
     pub last_data: RepositoryReport,
@@ -84,6 +87,31 @@ impl HistoryData {
         Ok(())
     }
 
+    /// The stored report scored by `scorer` with the thresholds in `config`,
+    /// as of the time it was checked rather than now. Reports saved before
+    /// check times were recorded keep their original score.
+    pub fn rescored(&self, scorer: &ProjectScorer, config: &Config) -> RepositoryReport {
+        let mut report = self.last_data.clone();
+        if let Some(checked_at) = report.checked_at {
+            scorer.evaluate_at(&mut report, &config.clone().with_defaults(), checked_at);
+        }
+        report
+    }
+
+    /// Records the stars `report` gained since this snapshot, and the days in between
+    pub fn record_star_growth(&self, report: &mut RepositoryReport) {
+        let (Some(previous), Some(current)) = (&self.last_data.popularity, &mut report.popularity) else {
+            return;
+        };
+        current.star_growth = Some(current.stars as i64 - previous.stars as i64);
+        current.star_growth_days = self
+            .last_data
+            .checked_at
+            .zip(report.checked_at)
+            .map(|(previous, current)| (current - previous).num_days());
+    }
+
+    /// Change magnitude of a field: absolute difference for numbers, days for dates, 0/1 otherwise
     pub fn calculate_change(&self, current: &RepositoryReport, field_path: &str) -> Result<i64> {
         // Extract values from both current and last data
         let current_value = extract_field_value(current, field_path)?;
@@ -184,29 +212,10 @@ mod tests {
         let file_path = dir.path().join("test_history.json");
         
         // Create test data
-        use crate::output::{LastCommitInfo, CriteriaInfo};
-        
         let report = RepositoryReport {
             owner: "test".to_string(),
-            repo: "repo".to_string(),
-            commits_total: 100,
-            contributors_total: 10,
-            open_pull_requests: 5,
-            open_issues: 20,
-            last_commit: LastCommitInfo {
-                sha: "abc123".to_string(),
-                author_name: "author".to_string(),
-                author_email: "author@test.com".to_string(),
-                date_utc: Utc::now(),
-                message: "test commit".to_string(),
-            },
-            last_release: None,
-            project_alive: true,
-            criteria: CriteriaInfo {
-                max_days: 60,
-                min_contributors: 3,
-                min_commits: 100,
-            },
+            commits_total: Some(100),
+            ..crate::report::sample_report()
         };
         
         let history = HistoryData { last_data: report };
@@ -216,6 +225,72 @@ mod tests {
         let loaded = HistoryData::load(&file_path, false).unwrap().unwrap();
         
         assert_eq!(loaded.last_data.owner, "test");
-        assert_eq!(loaded.last_data.commits_total, 100);
+        assert_eq!(loaded.last_data.commits_total, Some(100));
+    }
+
+    #[test]
+    fn test_rescored_as_of_snapshot_time() {
+        let checked_at = "2024-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut report = crate::report::sample_report();
+        report.last_commit.date_utc = checked_at - chrono::Duration::days(10);
+        report.project_alive = false;
+        report.checked_at = Some(checked_at);
+        let history = HistoryData { last_data: report };
+
+        // Ten days old at the snapshot's time, however long ago that is now
+        let rescored = history.rescored(&ProjectScorer::new(), &Config::default());
+        assert!(rescored.project_alive);
+        assert!(rescored.score.recency > 0.9);
+        assert_eq!(rescored.checked_at, Some(checked_at));
+
+        let legacy = HistoryData { last_data: RepositoryReport { checked_at: None, ..history.last_data.clone() } };
+        assert!(!legacy.rescored(&ProjectScorer::new(), &Config::default()).project_alive);
+    }
+
+    #[test]
+    fn test_star_growth() {
+        use crate::report::Popularity;
+
+        let checked_at = "2024-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let history = HistoryData {
+            last_data: RepositoryReport {
+                popularity: Some(Popularity { stars: 1200, ..Default::default() }),
+                checked_at: Some(checked_at),
+                ..crate::report::sample_report()
+            },
+        };
+        let mut current = RepositoryReport {
+            popularity: Some(Popularity { stars: 1150, ..Default::default() }),
+            checked_at: Some(checked_at + chrono::Duration::days(30)),
+            ..crate::report::sample_report()
+        };
+        history.record_star_growth(&mut current);
+        let popularity = current.popularity.as_ref().unwrap();
+        assert_eq!((popularity.star_growth, popularity.star_growth_days), (Some(-50), Some(30)));
+        assert_eq!(history.calculate_change(&current, "popularity.stars").unwrap(), 50);
+
+        // Nothing to compare with a snapshot from before popularity was recorded
+        let legacy = HistoryData { last_data: crate::report::sample_report() };
+        let mut current = RepositoryReport { popularity: Some(Popularity::default()), ..crate::report::sample_report() };
+        legacy.record_star_growth(&mut current);
+        assert_eq!(current.popularity.unwrap().star_growth, None);
+    }
+
+    #[test]
+    fn test_issue_health_change() {
+        use crate::report::IssueHealth;
+
+        let health = IssueHealth { opened_90d: 12, untouched_percent: Some(40.0), ..Default::default() };
+        let history = HistoryData {
+            last_data: RepositoryReport { issues: Some(health.clone()), ..crate::report::sample_report() },
+        };
+        let current = RepositoryReport {
+            issues: Some(IssueHealth { opened_90d: 20, untouched_percent: Some(25.5), ..health }),
+            ..crate::report::sample_report()
+        };
+        assert_eq!(history.calculate_change(&current, "issues.opened_90d").unwrap(), 8);
+        assert_eq!(history.calculate_change(&current, "issues.untouched_percent").unwrap(), 14);
+        // Unknown response times on both sides are no change
+        assert_eq!(history.calculate_change(&current, "issues.median_first_response_hours").unwrap(), 0);
     }
 }
\ No newline at end of file
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use reqwest::header::{self, HeaderMap};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+mod cache;
+mod cassette;
+
+pub use cache::{DEFAULT_TTL, ResponseCache};
+pub use cassette::Cassette;
+
+/// Requests a forge client sends at the same time unless configured otherwise
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// HTTP layer shared by the forge clients: a reqwest client plus a limit on
+/// the number of requests in flight, so concurrently fetched metrics and batch
+/// entries don't run into the forge's secondary rate limits, an optional
+/// on-disk [`ResponseCache`] and an optional [`Cassette`] to record or replay
+/// every exchange.
+#[derive(Clone)]
+pub(crate) struct Transport {
+    client: Client,
+    permits: Arc<Semaphore>,
+    cache: Option<ResponseCache>,
+    /// Credentials the responses depend on, part of the cache key
+    identity: String,
+    cassette: Option<Cassette>,
+}
+
+/// A response read to the end, possibly served from the cache
+#[derive(Debug)]
+pub(crate) struct HttpResponse {
+    url: String,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Transport {
+    pub(crate) fn new(client: Client, concurrency: usize) -> Self {
+        Transport {
+            client,
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            cache: None,
+            identity: String::new(),
+            cassette: None,
+        }
+    }
+
+    /// Caches responses; `identity` (e.g. the token) keeps different credentials apart
+    pub(crate) fn with_cache(mut self, cache: Option<ResponseCache>, identity: Option<&str>) -> Self {
+        self.cache = cache;
+        self.identity = identity.unwrap_or_default().to_string();
+        self
+    }
+
+    /// Records or replays every exchange, the cache is bypassed then
+    pub(crate) fn with_cassette(mut self, cassette: Option<Cassette>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    pub(crate) fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub(crate) fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Sends a request once one of the permits is free, answering from the
+    /// cache when possible
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<HttpResponse> {
+        let mut request = request.build().context("Invalid request")?;
+        if let Some(cassette) = &self.cassette {
+            if cassette.is_replay() {
+                return cassette.play(&request);
+            }
+            let recorded = request.try_clone().context("Request can't be recorded")?;
+            let response = self.execute(request).await?;
+            cassette.save(&recorded, &response)?;
+            return Ok(response);
+        }
+        let Some(cache) = &self.cache else {
+            return self.execute(request).await;
+        };
+
+        let key = cache.key(&self.identity, &request);
+        let cached = cache.load(&key);
+        match cached {
+            Some(entry) if cache.is_offline() || cache.is_fresh(&entry) => return Ok(entry.into_response()),
+            None if cache.is_offline() => {
+                anyhow::bail!("{} is not cached, can't fetch it in offline mode", request.url())
+            }
+            _ => {}
+        }
+
+        if let Some(entry) = &cached {
+            entry.add_validators(request.headers_mut());
+        }
+        let response = self.execute(request).await?;
+        match cached {
+            Some(mut entry) if response.status == StatusCode::NOT_MODIFIED => {
+                entry.touch();
+                cache.store(&key, &entry);
+                Ok(entry.into_response())
+            }
+            _ => {
+                if cache::is_cacheable(response.status) {
+                    cache.store(&key, &cache::CacheEntry::new(&response));
+                }
+                Ok(response)
+            }
+        }
+    }
+
+    async fn execute(&self, request: reqwest::Request) -> Result<HttpResponse> {
+        let _permit = self.permits.acquire().await.expect("semaphore is never closed");
+        let url = request.url().to_string();
+        let response = self.client.execute(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await.with_context(|| format!("Failed to read response from {url}"))?;
+        Ok(HttpResponse { url, status, headers, body: body.to_vec() })
+    }
+}
+
+impl HttpResponse {
+    pub(crate) fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub(crate) fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// A 403 or 429 of a primary or secondary rate limit: no requests remaining,
+    /// a `retry-after` header or a rate limit message
+    pub(crate) fn rate_limited(&self) -> bool {
+        let limit_status = matches!(self.status, StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS);
+        let exhausted = self.headers.get("x-ratelimit-remaining").is_some_and(|remaining| remaining == "0");
+        let message = String::from_utf8_lossy(&self.body).to_lowercase().contains("rate limit");
+        limit_status && (exhausted || self.headers.contains_key(header::RETRY_AFTER) || message)
+    }
+
+    /// Fails for 4xx and 5xx responses, like [`reqwest::Response::error_for_status`]
+    pub(crate) fn error_for_status(self) -> Result<Self> {
+        if self.status.is_client_error() || self.status.is_server_error() {
+            anyhow::bail!("HTTP status {} for url ({})", self.status, self.url);
+        }
+        Ok(self)
+    }
+
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.body)
+    }
+
+    pub(crate) fn text(&self) -> Result<String> {
+        String::from_utf8(self.body.clone()).context("Response is not valid UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let transport = Transport::new(Client::new(), 0);
+        assert_eq!(transport.permits.available_permits(), 1);
+
+        let transport = Transport::new(Client::new(), 3);
+        let _held = transport.permits.acquire().await.unwrap();
+        assert_eq!(transport.clone().permits.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_offline_serves_only_cached_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path()).offline(true);
+        let transport = Transport::new(Client::new(), 1).with_cache(Some(cache.clone()), Some("token"));
+
+        let url = "https://api.github.com/repos/a/b";
+        let key = cache.key("token", &transport.get(url).build().unwrap());
+        let response = HttpResponse {
+            url: url.to_string(),
+            status: StatusCode::NOT_FOUND,
+            headers: HeaderMap::new(),
+            body: b"{}".to_vec(),
+        };
+        cache.store(&key, &cache::CacheEntry::new(&response));
+
+        let response = transport.send(transport.get(url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.error_for_status().is_err());
+
+        let err = transport.send(transport.get("https://api.github.com/repos/a/c")).await.unwrap_err();
+        assert!(err.to_string().contains("offline mode"));
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        let response = |status: StatusCode, headers: &[(&'static str, &'static str)], body: &str| HttpResponse {
+            url: "https://api.github.com/repos/a/b/vulnerability-alerts".to_string(),
+            status,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (header::HeaderName::from_static(name), value.parse().unwrap()))
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        };
+        let forbidden = r#"{"message": "Must have admin rights to Repository."}"#;
+        assert!(!response(StatusCode::FORBIDDEN, &[("x-ratelimit-remaining", "4999")], forbidden).rate_limited());
+        assert!(response(StatusCode::FORBIDDEN, &[("x-ratelimit-remaining", "0")], forbidden).rate_limited());
+        // Secondary rate limits leave requests remaining
+        assert!(response(StatusCode::FORBIDDEN, &[("retry-after", "60")], "{}").rate_limited());
+        let secondary = r#"{"message": "You have exceeded a secondary rate limit. Please wait a few minutes."}"#;
+        assert!(response(StatusCode::FORBIDDEN, &[("x-ratelimit-remaining", "4990")], secondary).rate_limited());
+        assert!(!response(StatusCode::OK, &[("retry-after", "60")], "{}").rate_limited());
+    }
+}
@@ -0,0 +1,243 @@
+use chrono::Utc;
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::HttpResponse;
+
+/// How long responses are served without revalidation unless configured otherwise
+pub const DEFAULT_TTL: Duration = Duration::from_secs(600);
+
+/// On-disk cache of API responses, one JSON file per request.
+///
+/// Fresh entries (younger than the TTL) are served without a request. Stale
+/// entries are revalidated with `If-None-Match`/`If-Modified-Since`; a
+/// `304 Not Modified` answer, which GitHub doesn't count against the rate
+/// limit, renews them. In offline mode only cached responses are served.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    offline: bool,
+}
+
+/// A cached response with the validators needed to revalidate it
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    /// Unix timestamp of the last store or successful revalidation
+    stored_at: i64,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ResponseCache {
+            dir: dir.into(),
+            ttl: DEFAULT_TTL,
+            offline: false,
+        }
+    }
+
+    /// `$XDG_CACHE_HOME/github-activity-check`, or `~/.cache/github-activity-check`
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(base.join("github-activity-check"))
+    }
+
+    /// Time a response is served without revalidation (default: 10 minutes)
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Serves only cached responses, requests that aren't cached fail
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub(crate) fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub(crate) fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        let age = Utc::now().timestamp() - entry.stored_at;
+        age >= 0 && (age as u64) < self.ttl.as_secs()
+    }
+
+    /// Hash of credentials, method, URL and body; tokens never end up on disk
+    pub(crate) fn key(&self, identity: &str, request: &reqwest::Request) -> String {
+        let mut hasher = Sha256::new();
+        for part in [identity.as_bytes(), request.method().as_str().as_bytes(), request.url().as_str().as_bytes()] {
+            hasher.update(part);
+            hasher.update([0]);
+        }
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            hasher.update(body);
+        }
+        hasher.finalize().iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    pub(crate) fn load(&self, key: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Stores an entry; the cache is best effort, failures only lose the entry
+    pub(crate) fn store(&self, key: &str, entry: &CacheEntry) {
+        let Ok(content) = serde_json::to_string(entry) else {
+            return;
+        };
+        // Write and rename so concurrent readers never see a partial file
+        let tmp = self.dir.join(format!("{key}.{}.tmp", std::process::id()));
+        let stored = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp, content))
+            .and_then(|_| fs::rename(&tmp, self.path(key)));
+        if stored.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Final responses and 404s (e.g. "no release") are worth keeping; a 202 is a
+/// placeholder while GitHub computes statistics and must be asked again
+pub(crate) fn is_cacheable(status: StatusCode) -> bool {
+    matches!(status, StatusCode::OK | StatusCode::NON_AUTHORITATIVE_INFORMATION | StatusCode::NOT_FOUND)
+}
+
+impl CacheEntry {
+    pub(crate) fn new(response: &HttpResponse) -> Self {
+        CacheEntry {
+            url: response.url.clone(),
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect(),
+            body: String::from_utf8_lossy(&response.body).into_owned(),
+            stored_at: Utc::now().timestamp(),
+        }
+    }
+
+    fn header(&self, name: &HeaderName) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.as_str()))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Adds the conditional request headers for this entry
+    pub(crate) fn add_validators(&self, headers: &mut HeaderMap) {
+        let validators = [
+            (header::ETAG, header::IF_NONE_MATCH),
+            (header::LAST_MODIFIED, header::IF_MODIFIED_SINCE),
+        ];
+        for (validator, condition) in validators {
+            if let Some(value) = self.header(&validator).and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(condition, value);
+            }
+        }
+    }
+
+    /// Marks the entry as revalidated now
+    pub(crate) fn touch(&mut self) {
+        self.stored_at = Utc::now().timestamp();
+    }
+
+    pub(crate) fn into_response(self) -> HttpResponse {
+        let headers = self
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((HeaderName::from_bytes(name.as_bytes()).ok()?, HeaderValue::from_str(value).ok()?))
+            })
+            .collect();
+        HttpResponse {
+            url: self.url,
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: self.body.into_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn response(etag: &str) -> HttpResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ETAG, HeaderValue::from_str(etag).unwrap());
+        headers.insert(header::LINK, HeaderValue::from_static("<https://x/?page=7>; rel=\"last\""));
+        HttpResponse {
+            url: "https://api.github.com/repos/a/b".to_string(),
+            status: StatusCode::OK,
+            headers,
+            body: b"{\"ok\":true}".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_store_and_load() {
+        let dir = tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path());
+        cache.store("abc", &CacheEntry::new(&response("\"v1\"")));
+
+        let entry = cache.load("abc").unwrap();
+        assert!(cache.is_fresh(&entry));
+        assert!(!cache.clone().ttl(Duration::ZERO).is_fresh(&entry));
+
+        let mut headers = HeaderMap::new();
+        entry.add_validators(&mut headers);
+        assert_eq!(headers[header::IF_NONE_MATCH], "\"v1\"");
+
+        let response = entry.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::LINK].to_str().unwrap().contains("page=7"));
+        assert_eq!(response.json::<serde_json::Value>().unwrap()["ok"], true);
+        assert!(cache.load("missing").is_none());
+    }
+
+    #[test]
+    fn test_key_depends_on_identity_and_request() {
+        let cache = ResponseCache::new("/nonexistent");
+        let client = reqwest::Client::new();
+        let get = client.get("https://api.github.com/repos/a/b").build().unwrap();
+        let other = client.get("https://api.github.com/repos/a/c").build().unwrap();
+        let post = client.post("https://api.github.com/graphql").body("{\"query\":1}").build().unwrap();
+        let post2 = client.post("https://api.github.com/graphql").body("{\"query\":2}").build().unwrap();
+
+        let key = cache.key("token", &get);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, cache.key("token", &get));
+        assert_ne!(key, cache.key("other-token", &get));
+        assert_ne!(key, cache.key("token", &other));
+        assert_ne!(cache.key("token", &post), cache.key("token", &post2));
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        assert!(is_cacheable(StatusCode::OK));
+        assert!(is_cacheable(StatusCode::NOT_FOUND));
+        assert!(!is_cacheable(StatusCode::ACCEPTED));
+        assert!(!is_cacheable(StatusCode::NO_CONTENT));
+        assert!(!is_cacheable(StatusCode::FORBIDDEN));
+        assert!(!is_cacheable(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+}
@@ -0,0 +1,210 @@
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::HttpResponse;
+
+/// Recorded HTTP exchanges for deterministic runs and tests.
+///
+/// In record mode every request is sent and its response saved as one JSON
+/// file in the directory. In replay mode responses are served from those files
+/// and nothing goes over the network; a request that wasn't recorded fails.
+/// Exchanges are matched by method, URL and request body, so file names are
+/// free to choose and tokens never end up in a cassette.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    dir: PathBuf,
+    /// Recorded exchanges by request, loaded once in replay mode
+    replay: Option<Arc<HashMap<String, Exchange>>>,
+}
+
+/// One request and its response as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    method: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    /// Body of JSON responses, kept as JSON so cassettes are easy to read and edit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<Value>,
+    /// Body of other responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+/// Headers that aren't worth recording
+const SKIPPED_HEADERS: [&str; 3] = ["set-cookie", "date", "x-github-request-id"];
+
+impl Cassette {
+    /// Records every exchange into `dir`
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Cassette { dir: dir.into(), replay: None }
+    }
+
+    /// Serves the exchanges recorded in `dir`
+    pub fn replay(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        let mut exchanges = HashMap::new();
+        let entries = fs::read_dir(&dir).with_context(|| format!("Failed to read cassette directory {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path)?;
+            let exchange: Exchange = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid cassette file {}", path.display()))?;
+            exchanges.insert(match_key(&exchange.method, &exchange.url, exchange.request_body.as_deref()), exchange);
+        }
+        Ok(Cassette { dir, replay: Some(Arc::new(exchanges)) })
+    }
+
+    pub(crate) fn is_replay(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Recorded response for the request
+    pub(crate) fn play(&self, request: &reqwest::Request) -> Result<HttpResponse> {
+        let body = request_body(request);
+        let exchange = self
+            .replay
+            .as_ref()
+            .and_then(|exchanges| exchanges.get(&match_key(request.method().as_str(), request.url().as_str(), body.as_deref())))
+            .with_context(|| {
+                format!("No recorded response for {} {} in {}", request.method(), request.url(), self.dir.display())
+            })?;
+        Ok(exchange.clone().into_response())
+    }
+
+    /// Saves an exchange as `<path>-<hash>.json`
+    pub(crate) fn save(&self, request: &reqwest::Request, response: &HttpResponse) -> Result<()> {
+        let body = request_body(request);
+        let exchange = Exchange::new(request, body, response);
+        let key = match_key(&exchange.method, &exchange.url, exchange.request_body.as_deref());
+        let path = self.dir.join(format!("{}-{}.json", file_stem(request.url()), &key[..8]));
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, serde_json::to_string_pretty(&exchange)? + "\n")
+            .with_context(|| format!("Failed to write cassette file {}", path.display()))
+    }
+}
+
+fn request_body(request: &reqwest::Request) -> Option<String> {
+    let bytes = request.body()?.as_bytes()?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn match_key(method: &str, url: &str, body: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    for part in [method, url, body.unwrap_or_default()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Readable file name from the URL path and query, e.g. `repos_owner_repo_commits_per_page_1`
+fn file_stem(url: &reqwest::Url) -> String {
+    let raw = format!("{}{}", url.path(), url.query().map(|q| format!("?{q}")).unwrap_or_default());
+    let stem: String = raw
+        .trim_start_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .take(80)
+        .collect();
+    if stem.is_empty() { "root".to_string() } else { stem }
+}
+
+impl Exchange {
+    fn new(request: &reqwest::Request, request_body: Option<String>, response: &HttpResponse) -> Self {
+        let headers = response
+            .headers
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let (json, text) = match serde_json::from_slice::<Value>(&response.body) {
+            Ok(json) => (Some(json), None),
+            Err(_) if response.body.is_empty() => (None, None),
+            Err(_) => (None, Some(String::from_utf8_lossy(&response.body).into_owned())),
+        };
+        Exchange {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            request_body,
+            status: response.status.as_u16(),
+            headers,
+            json,
+            text,
+        }
+    }
+
+    fn into_response(self) -> HttpResponse {
+        let headers: HeaderMap = self
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((HeaderName::from_bytes(name.as_bytes()).ok()?, HeaderValue::from_str(value).ok()?))
+            })
+            .collect();
+        let body = match (self.json, self.text) {
+            (Some(json), _) => json.to_string().into_bytes(),
+            (None, Some(text)) => text.into_bytes(),
+            (None, None) => Vec::new(),
+        };
+        HttpResponse {
+            url: self.url,
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_then_replay() {
+        let dir = tempdir().unwrap();
+        let client = reqwest::Client::new();
+        let request = client.get("https://api.github.com/repos/a/b/commits?per_page=1").build().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::LINK, HeaderValue::from_static("<https://x/?page=42>; rel=\"last\""));
+        headers.insert(header::SET_COOKIE, HeaderValue::from_static("secret"));
+        let response = HttpResponse {
+            url: request.url().to_string(),
+            status: StatusCode::OK,
+            headers,
+            body: b"[{\"sha\":\"abc\"}]".to_vec(),
+        };
+
+        Cassette::record(dir.path()).save(&request, &response).unwrap();
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].to_string_lossy().starts_with("repos_a_b_commits_per_page_1-"));
+
+        let cassette = Cassette::replay(dir.path()).unwrap();
+        let replayed = cassette.play(&request).unwrap();
+        assert_eq!(replayed.status(), StatusCode::OK);
+        assert!(replayed.headers().contains_key(header::LINK));
+        assert!(!replayed.headers().contains_key(header::SET_COOKIE));
+        assert_eq!(replayed.json::<Value>().unwrap()[0]["sha"], "abc");
+
+        let other = client.get("https://api.github.com/repos/a/c").build().unwrap();
+        assert!(cassette.play(&other).unwrap_err().to_string().contains("No recorded response for GET"));
+    }
+}
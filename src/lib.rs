@@ -0,0 +1,124 @@
+//! Check whether GitHub, GitLab, Gitea and Bitbucket repositories are actively maintained.
+//!
+//! The crate fetches commits, contributors, pull requests, issues and releases
+//! of a repository through a [`ForgeClient`], scores them with [`ProjectScorer`]
+//! and returns a [`RepositoryReport`]. The `github-activity-check` binary is a
+//! thin wrapper around this API.
+//!
+//! ```no_run
+//! use github_activity_check::{Config, check_repository};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let report = check_repository("serde-rs", "serde", &Config::default()).await?;
+//! println!("alive: {} (score {:.2})", report.project_alive, report.score.total);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use [`check_repository_with`] to reuse a configured [`GitHubClient`] (or any
+//! other [`ForgeClient`]) and [`ProjectScorer`] across several repositories, and
+//! [`check_target`] for repositories on other forges.
+
+use anyhow::Result;
+
+/// Prints to stderr when `config.verbose` is set; the binary has its own copy
+macro_rules! verbose_println {
+    ($config:expr, $($arg:tt)*) => {
+        if $config.verbose {
+            eprintln!("[VERBOSE] {}", format!($($arg)*));
+        }
+    };
+}
+pub(crate) use verbose_println;
+
+pub mod batch;
+pub mod bitbucket;
+pub mod clock;
+pub mod config;
+pub mod forge;
+pub mod gitea;
+pub mod github;
+pub mod gitlab;
+pub mod history;
+mod http;
+pub mod local;
+pub mod output;
+pub mod report;
+pub mod scoring;
+pub mod types;
+
+pub use batch::{BatchEntry, BatchResult};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use config::Config;
+pub use bitbucket::{BitbucketCloudClient, BitbucketServerClient};
+pub use forge::{AnyForge, FetchOptions, ForgeClient, ForgeKind, RepoTarget, RepositoryData, Unsupported};
+pub use gitea::{GiteaClient, GiteaClientBuilder};
+pub use github::{GitHubClient, GitHubClientBuilder};
+pub use gitlab::{GitLabClient, GitLabClientBuilder};
+pub use history::HistoryData;
+pub use http::{Cassette, ResponseCache};
+pub use local::LocalRepoClient;
+pub use output::OutputFormat;
+pub use report::{CiState, ReleaseSource, RepositoryReport, SecurityState};
+pub use scoring::{ProjectScorer, ScoreBreakdown, Verdict};
+
+/// Checks a repository with a client authenticated by the `GITHUB_TOKEN`
+/// environment variable (if set) and the default scorer.
+///
+/// Settings missing in `config` fall back to their defaults; its `owner` and
+/// `repo` fields are ignored.
+pub async fn check_repository(owner: &str, repo: &str, config: &Config) -> Result<RepositoryReport> {
+    let token = std::env::var("GITHUB_TOKEN").ok();
+    let client = GitHubClient::new(token.as_deref())?;
+    check_repository_with(&client, &ProjectScorer::new(), owner, repo, config).await
+}
+
+/// Checks a repository on any supported forge with a client created by
+/// [`AnyForge::connect`] and the default scorer
+pub async fn check_target(target: &RepoTarget, config: &Config) -> Result<RepositoryReport> {
+    let forge = AnyForge::connect(target, config)?;
+    check_repository_with(&forge, &ProjectScorer::new(), &target.owner, &target.repo, config).await
+}
+
+/// Checks a repository with the given forge client and scorer
+pub async fn check_repository_with<F: ForgeClient>(
+    forge: &F,
+    scorer: &ProjectScorer,
+    owner: &str,
+    repo: &str,
+    config: &Config,
+) -> Result<RepositoryReport> {
+    verbose_println!(config, "Fetching repository data for {owner}/{repo} from {} API...", forge.name());
+
+    // Activity windows end at the time the report is scored at, see `--as-of`
+    let data = forge.fetch_repository(owner, repo, &FetchOptions::for_config(config, scorer.now())).await?;
+    Ok(build_report(scorer, owner, repo, config, &data))
+}
+
+/// Creates the scored report for fetched repository data
+pub(crate) fn build_report(
+    scorer: &ProjectScorer,
+    owner: &str,
+    repo: &str,
+    config: &Config,
+    data: &RepositoryData,
+) -> RepositoryReport {
+    let mut config = config.clone().with_defaults();
+    config.owner = Some(owner.to_string());
+    config.repo = Some(repo.to_string());
+
+    let mut report = report::create_repository_report(&config, data);
+    let now = scorer.now();
+    report.cadence = data.commit_activity.as_ref().map(|activity| activity.cadence(now));
+    report.bus_factor = data.contributor_activity.as_ref().map(|activity| activity.bus_factor(now));
+    report.pull_requests = data
+        .pull_request_activity
+        .as_ref()
+        .map(|activity| activity.responsiveness(now, config.get_pr_window_days(), config.get_stale_pr_days()));
+    report.issues = data.issue_activity.as_ref().map(|activity| activity.health(now));
+    report.releases = data.release_history.as_ref().map(|history| history.cadence(now));
+    report.ci = data.ci_activity.as_ref().map(|activity| activity.health(now, config.get_ci_runs()));
+    report.security = data.security.as_ref().map(|security| security.posture(now));
+    scorer.evaluate_at(&mut report, &config, now);
+    report
+}
@@ -0,0 +1,292 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::forge::{ForgeClient, Unsupported, from_commits};
+use crate::types::{
+    ACTIVITY_DAYS, AuthorMeta, CommitActivity, CommitInfo, CommitMeta, ContributorActivity, ReleaseHistory,
+    ReleaseInfo,
+};
+
+/// Reads repository data from a local clone with the `git` command line tool,
+/// no forge API involved. Pull requests and issues are unsupported.
+pub struct LocalRepoClient {
+    path: PathBuf,
+    /// Revision of the default branch: `origin/HEAD` when known, `HEAD` otherwise
+    rev: String,
+}
+
+impl LocalRepoClient {
+    /// Opens the git repository at `path` (work tree or bare)
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        git(&path, &["rev-parse", "--git-dir"])
+            .await
+            .with_context(|| format!("{} is not a git repository", path.display()))?;
+
+        let rev = match git(&path, &["symbolic-ref", "-q", "refs/remotes/origin/HEAD"]).await {
+            Ok(origin_head) if !origin_head.trim().is_empty() => origin_head.trim().to_string(),
+            _ => "HEAD".to_string(),
+        };
+        Ok(LocalRepoClient { path, rev })
+    }
+
+    /// Name of the repository directory, used as the report's repo name
+    pub fn dir_name(&self) -> String {
+        let path = self.path.canonicalize().unwrap_or_else(|_| self.path.clone());
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        name.trim_end_matches(".git").to_string()
+    }
+}
+
+/// Runs git in the repository and returns its stdout
+async fn git(path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run git, is it installed?")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("git produced invalid UTF-8")
+}
+
+/// `--since` and `--until` options for the [`ACTIVITY_DAYS`] before `now`
+fn window(now: DateTime<Utc>) -> [String; 2] {
+    [
+        format!("--since={}", (now - Duration::days(ACTIVITY_DAYS)).to_rfc3339()),
+        format!("--until={}", now.to_rfc3339()),
+    ]
+}
+
+impl ForgeClient for LocalRepoClient {
+    fn name(&self) -> &'static str {
+        "local git"
+    }
+
+    async fn get_last_commit(&self, _owner: &str, _repo: &str) -> Result<CommitInfo> {
+        // %aN/%aE respect .mailmap
+        let out = git(&self.path, &["log", "-1", "--format=%H%x00%aN%x00%aE%x00%aI%x00%B", &self.rev])
+            .await
+            .with_context(|| format!("Repository {} has no commits", self.path.display()))?;
+        let mut fields = out.splitn(5, '\0');
+        let mut next = || fields.next().context("Unexpected git log output");
+        let sha = next()?.to_string();
+        let name = next()?.to_string();
+        let email = next()?.to_string();
+        let date: DateTime<Utc> = DateTime::parse_from_rfc3339(next()?)
+            .context("Invalid commit date")?
+            .with_timezone(&Utc);
+        let message = next()?.trim_end().to_string();
+        Ok(CommitInfo {
+            sha,
+            commit: CommitMeta {
+                author: AuthorMeta { name, email, date },
+                message,
+            },
+        })
+    }
+
+    async fn get_commit_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        git(&self.path, &["rev-list", "--count", &self.rev])
+            .await?
+            .trim()
+            .parse()
+            .context("Invalid commit count")
+    }
+
+    /// Distinct author emails on the default branch after applying .mailmap
+    async fn get_contributors_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        let out = git(&self.path, &["log", "--use-mailmap", "--format=%aE", &self.rev]).await?;
+        let authors: HashSet<String> = out
+            .lines()
+            .map(|email| email.trim().to_lowercase())
+            .filter(|email| !email.is_empty())
+            .collect();
+        Ok(authors.len())
+    }
+
+    /// Both from one `git log`; committers are identified by email after applying .mailmap
+    async fn get_commit_history(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        now: DateTime<Utc>,
+    ) -> (Result<CommitActivity>, Result<ContributorActivity>) {
+        let [since, until] = window(now);
+        let commits = async {
+            let args = ["log", "--use-mailmap", &since, &until, "--format=%aE%x00%aI", &self.rev];
+            let out = git(&self.path, &args).await?;
+            out.lines()
+                .map(|line| {
+                    let (email, date) = line.split_once('\0').context("Unexpected git log output")?;
+                    let date = DateTime::parse_from_rfc3339(date.trim()).context("Invalid commit date")?;
+                    Ok((email.trim().to_lowercase(), date.with_timezone(&Utc)))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        from_commits(commits.await.map(Vec::into_iter))
+    }
+
+    async fn get_open_prs_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        Err(Unsupported { forge: "local git", metric: "Pull requests" }.into())
+    }
+
+    async fn get_open_issues_count(&self, _owner: &str, _repo: &str) -> Result<usize> {
+        Err(Unsupported { forge: "local git", metric: "Issues" }.into())
+    }
+
+    /// The most recently created tag stands in for a release
+    async fn get_latest_release(&self, _owner: &str, _repo: &str) -> Result<Option<ReleaseInfo>> {
+        let out = git(
+            &self.path,
+            &[
+                "for-each-ref",
+                "--sort=-creatordate",
+                "--count=1",
+                "--format=%(refname:short)%00%(creatordate:iso-strict)",
+                "refs/tags",
+            ],
+        )
+        .await?;
+        let Some((tag, date)) = out.trim().split_once('\0') else {
+            return Ok(None);
+        };
+        Ok(Some(ReleaseInfo {
+            tag_name: tag.to_string(),
+            name: None,
+            published_at: DateTime::parse_from_rfc3339(date).ok().map(|d| d.with_timezone(&Utc)),
+            prerelease: false,
+            draft: false,
+        }))
+    }
+
+    /// Version tags by creation date, the tagger's for annotated tags
+    async fn get_release_history(&self, _owner: &str, _repo: &str, _now: DateTime<Utc>) -> Result<ReleaseHistory> {
+        let out = git(
+            &self.path,
+            &["for-each-ref", "--format=%(refname:short)%00%(creatordate:iso-strict)", "refs/tags"],
+        )
+        .await?;
+        let tags = out.lines().filter_map(|line| {
+            let (tag, date) = line.split_once('\0')?;
+            let date = DateTime::parse_from_rfc3339(date.trim()).ok()?;
+            Some((tag.to_string(), date.with_timezone(&Utc)))
+        });
+        Ok(ReleaseHistory::from_tags(tags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::scoring::ProjectScorer;
+    use tempfile::tempdir;
+
+    /// Runs git with a fixed identity and date so fixtures are reproducible
+    fn git_fixture(path: &Path, args: &[&str], author: (&str, &str), date: &str) {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(args)
+            .env("GIT_AUTHOR_NAME", author.0)
+            .env("GIT_AUTHOR_EMAIL", author.1)
+            .env("GIT_COMMITTER_NAME", author.0)
+            .env("GIT_COMMITTER_EMAIL", author.1)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("HOME", path)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn create_fixture_repo(path: &Path) {
+        let alice = ("Alice", "alice@example.com");
+        let alice_old = ("alice", "alice@old-laptop.local");
+        let bob = ("Bob", "bob@example.com");
+
+        git_fixture(path, &["init", "-q", "-b", "main"], alice, "2024-01-01T10:00:00Z");
+        git_fixture(path, &["commit", "-q", "--allow-empty", "-m", "Initial commit"], alice, "2024-01-01T10:00:00Z");
+        git_fixture(path, &["commit", "-q", "--allow-empty", "-m", "Add parser"], alice_old, "2024-02-01T10:00:00Z");
+        git_fixture(path, &["tag", "-a", "v0.1.0", "-m", "v0.1.0"], alice, "2024-02-01T12:00:00Z");
+        git_fixture(path, &["commit", "-q", "--allow-empty", "-m", "Fix bug\n\nDetails"], bob, "2024-03-01T10:00:00Z");
+        std::fs::write(
+            path.join(".mailmap"),
+            "Alice <alice@example.com> alice <alice@old-laptop.local>\n",
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_local_repository_metrics() {
+        let dir = tempdir().unwrap();
+        create_fixture_repo(dir.path());
+        let client = LocalRepoClient::open(dir.path()).await.unwrap();
+
+        let commit = client.get_last_commit("local", "repo").await.unwrap();
+        assert_eq!(commit.commit.author.name, "Bob");
+        assert_eq!(commit.commit.author.date.to_rfc3339(), "2024-03-01T10:00:00+00:00");
+        assert_eq!(commit.commit.message, "Fix bug\n\nDetails");
+
+        assert_eq!(client.get_commit_count("local", "repo").await.unwrap(), 3);
+        // alice@old-laptop.local is mapped to alice@example.com by .mailmap
+        assert_eq!(client.get_contributors_count("local", "repo").await.unwrap(), 2);
+
+        let release = client.get_latest_release("local", "repo").await.unwrap().unwrap();
+        assert_eq!(release.tag_name, "v0.1.0");
+        assert_eq!(release.published_at.unwrap().to_rfc3339(), "2024-02-01T12:00:00+00:00");
+        let now = "2024-03-01T12:00:00Z".parse().unwrap();
+        let history = client.get_release_history("local", "repo", now).await.unwrap();
+        let cadence = history.cadence(now);
+        assert_eq!(cadence.source, crate::report::ReleaseSource::Tags);
+        assert_eq!((cadence.releases_365d, cadence.days_since_stable), (1, Some(29)));
+
+        // Only the commits of the year before the given time are listed
+        let at = |date: &str| date.parse::<DateTime<Utc>>().unwrap();
+        let activity = client.get_commit_history("local", "repo", at("2024-02-15T00:00:00Z")).await.0.unwrap();
+        assert_eq!(activity, [at("2024-01-01T10:00:00Z"), at("2024-02-01T10:00:00Z")].into_iter().collect());
+        let activity = client.get_commit_history("local", "repo", at("2025-01-15T00:00:00Z")).await.0.unwrap();
+        assert_eq!(activity, [at("2024-02-01T10:00:00Z"), at("2024-03-01T10:00:00Z")].into_iter().collect());
+
+        let err = client.get_open_issues_count("local", "repo").await.unwrap_err();
+        assert!(err.downcast_ref::<Unsupported>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_local_repository_report() {
+        let dir = tempdir().unwrap();
+        create_fixture_repo(dir.path());
+        let client = LocalRepoClient::open(dir.path()).await.unwrap();
+
+        let report = crate::check_repository_with(&client, &ProjectScorer::new(), "local", "repo", &Config::default())
+            .await
+            .unwrap();
+        assert_eq!(report.commits_total, Some(3));
+        assert_eq!(report.contributors_total, Some(2));
+        assert_eq!(report.open_pull_requests, None);
+        assert_eq!(report.unsupported, vec!["open_pull_requests", "open_issues", "security", "community", "license", "popularity", "metadata"]);
+        assert_eq!(report.last_release.unwrap().tag_name, "v0.1.0");
+        assert_eq!(report.last_commit.message, "Fix bug");
+        // All fixture commits are older than a year
+        assert_eq!(report.cadence.map(|cadence| cadence.commits_365d), Some(0));
+        assert_eq!(report.bus_factor.map(|bus_factor| bus_factor.active_365d), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_not_a_repository() {
+        let dir = tempdir().unwrap();
+        assert!(LocalRepoClient::open(dir.path()).await.is_err());
+    }
+}
@@ -1,18 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use std::collections::BTreeMap;
 
-mod config;
-mod github;
-mod history;
-mod output;
-mod scoring;
-mod types;
-
-use config::Config;
-use github::GitHubClient;
-use history::HistoryData;
-use output::{create_repository_report, print_output};
-use scoring::ProjectScorer;
+use github_activity_check::batch::{check_batch, load_batch_file};
+use github_activity_check::output::{print_batch_output, print_batch_progress, print_output};
+use github_activity_check::{
+    AnyForge, Config, FixedClock, HistoryData, LocalRepoClient, ProjectScorer, RepoTarget, check_repository_with,
+};
 
 macro_rules! verbose_println {
     ($config:expr, $($arg:tt)*) => {
@@ -42,7 +36,6 @@ async fn main() -> Result<()> {
 
 async fn run() -> Result<()> {
     let config = Config::parse();
-    config.validate()?;
 
     // Load and merge configuration file if specified
     let config = if let Some(config_path) = &config.config_file {
@@ -52,45 +45,36 @@ async fn run() -> Result<()> {
     } else {
         config.with_defaults()
     };
+    // Validated after merging, forge hosts may come from the configuration file
+    config.validate()?;
 
-    let token = std::env::var("GITHUB_TOKEN").ok();
-    let github_client = GitHubClient::new(token.as_deref())?;
-    let scorer = ProjectScorer::new();
+    let mut scorer = ProjectScorer::new();
+    if let Some(as_of) = config.as_of {
+        verbose_println!(&config, "Scoring as of {as_of}");
+        scorer = scorer.with_clock(FixedClock(as_of));
+    }
 
-    verbose_println!(&config, "Fetching repository data from GitHub API...");
-    
-    let last_commit = github_client.get_last_commit(config.get_owner(), config.get_repo()).await?;
-    let commits_count = github_client.get_commit_count(config.get_owner(), config.get_repo()).await?;
-    let contributors_count = github_client.get_contributors_count(config.get_owner(), config.get_repo()).await?;
-    let open_prs = github_client.get_open_prs_count(config.get_owner(), config.get_repo()).await?;
-    let open_issues = github_client.get_open_issues_count(config.get_owner(), config.get_repo()).await?;
-    let latest_release = github_client.get_latest_release(config.get_owner(), config.get_repo()).await?;
-
-    let alive = scorer.is_project_alive(
-        &last_commit.commit.author.date,
-        commits_count,
-        contributors_count,
-        open_prs,
-        open_issues,
-        latest_release.as_ref(),
-        &config,
-    );
-
-    let current_report = create_repository_report(
-        &config,
-        commits_count,
-        contributors_count,
-        open_prs,
-        open_issues,
-        &last_commit,
-        latest_release.as_ref(),
-        alive,
-    );
+    if let Some(batch_path) = &config.batch {
+        return run_batch(&config, batch_path, &scorer).await;
+    }
+
+    let (forge, owner, repo) = if let Some(path) = &config.local {
+        let client = LocalRepoClient::open(path).await?;
+        let name = client.dir_name();
+        (AnyForge::Local(client), "local".to_string(), name)
+    } else {
+        let target = config.target()?;
+        (AnyForge::connect(&target, &config)?, target.owner, target.repo)
+    };
+    let mut current_report = check_repository_with(&forge, &scorer, &owner, &repo, &config).await?;
 
     // Handle history and check logic
     if let Some(history_path) = &config.history {
         // Load existing history
         let existing_history = HistoryData::load(history_path, config.verbose)?;
+        if let Some(history) = &existing_history {
+            history.record_star_growth(&mut current_report);
+        }
 
         // Save current data to history first (before checking for changes)
         let new_history = HistoryData {
@@ -102,7 +86,9 @@ async fn run() -> Result<()> {
         if let Some(check_field) = &config.check {
             verbose_println!(&config, "Checking field '{}' for changes", check_field);
             
-            if let Some(history) = existing_history {
+            if let Some(mut history) = existing_history {
+                // Score the previous run like this one, as of the time it was checked
+                history.last_data = history.rescored(&scorer, &config);
                 let change_magnitude = history.calculate_change(&current_report, check_field)?;
                 verbose_println!(&config, "Change magnitude for '{}': {}", check_field, change_magnitude);
                 std::process::exit(change_magnitude as i32);
@@ -121,3 +107,36 @@ async fn run() -> Result<()> {
 
     Ok(())
 }
+
+async fn run_batch(
+    config: &Config,
+    batch_path: &str,
+    scorer: &ProjectScorer,
+) -> Result<()> {
+    let entries = load_batch_file(batch_path, &config.hosts)?;
+    verbose_println!(config, "Loaded {} repositories from batch file: {}", entries.len(), batch_path);
+
+    // One client per host, shared by all of its repositories
+    let mut forges = BTreeMap::new();
+    for entry in &entries {
+        if !forges.contains_key(&entry.target.host) {
+            forges.insert(entry.target.host.clone(), AnyForge::connect(&entry.target, config)?);
+        }
+    }
+
+    let forge_for = |target: &RepoTarget| {
+        forges.get(&target.host).context("No client for host")
+    };
+    let results = check_batch(forge_for, scorer, config, entries, |result| {
+        print_batch_progress(config, result)
+    })
+    .await?;
+
+    print_batch_output(config, &results)?;
+
+    let failed = results.iter().filter(|r| r.report.is_err()).count();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} repositories could not be checked", results.len());
+    }
+    Ok(())
+}
@@ -1,24 +1,57 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-use crate::types::{CommitInfo, ReleaseInfo};
 use crate::config::Config;
+use crate::batch::BatchResult;
+use crate::types::TOP_CONTRIBUTORS;
 
+pub use crate::report::{
+    BusFactor, Cadence, CiHealth, CiState, CriteriaInfo, IssueHealth, LastCommitInfo, LastReleaseInfo, PullRequestResponsiveness,
+    CommunityProfile, License, Popularity, ReleaseCadence, ReleaseSource, RepositoryMetadata, RepositoryReport, SecurityPosture,
+    SecurityState,
+};
+use crate::scoring::Verdict;
+
+mod github_actions;
+mod junit;
+mod ndjson;
+mod sarif;
+
+/// How a report is printed
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Default,
     Json,
+    #[serde(rename = "github-actions")]
+    GitHubActions,
+    Sarif,
+    Junit,
+    Ndjson,
     Field(String),
 }
 
+impl OutputFormat {
+    /// Format used when none is given on the command line or in the config file
+    pub fn detect() -> Self {
+        if std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true") {
+            OutputFormat::GitHubActions
+        } else {
+            OutputFormat::Default
+        }
+    }
+}
+
 impl std::fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             OutputFormat::Default => write!(f, "default"),
             OutputFormat::Json => write!(f, "json"),
+            OutputFormat::GitHubActions => write!(f, "github-actions"),
+            OutputFormat::Sarif => write!(f, "sarif"),
+            OutputFormat::Junit => write!(f, "junit"),
+            OutputFormat::Ndjson => write!(f, "ndjson"),
             OutputFormat::Field(field) => write!(f, "field:{field}"),
         }
     }
@@ -31,6 +64,10 @@ impl FromStr for OutputFormat {
         match s {
             "default" => Ok(OutputFormat::Default),
             "json" => Ok(OutputFormat::Json),
+            "github-actions" => Ok(OutputFormat::GitHubActions),
+            "sarif" => Ok(OutputFormat::Sarif),
+            "junit" => Ok(OutputFormat::Junit),
+            "ndjson" => Ok(OutputFormat::Ndjson),
             s if s.starts_with("field:") => {
                 let field = s.strip_prefix("field:").unwrap_or("");
                 if field.is_empty() {
@@ -38,101 +75,116 @@ impl FromStr for OutputFormat {
                 }
                 Ok(OutputFormat::Field(field.to_string()))
             }
-            _ => anyhow::bail!("Invalid format '{}'. Use 'default', 'json', or 'field:field_name'", s),
+            _ => anyhow::bail!("Invalid format '{}'. Use 'default', 'json', 'github-actions', 'sarif', 'junit', 'ndjson', or 'field:field_name'", s),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RepositoryReport {
-    pub owner: String,
-    pub repo: String,
-    pub commits_total: usize,
-    pub contributors_total: usize,
-    pub open_pull_requests: usize,
-    pub open_issues: usize,
-    pub last_commit: LastCommitInfo,
-    pub last_release: Option<LastReleaseInfo>,
-    pub project_alive: bool,
-    pub criteria: CriteriaInfo,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LastCommitInfo {
-    pub sha: String,
-    pub author_name: String,
-    pub author_email: String,
-    pub date_utc: DateTime<Utc>,
-    pub message: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LastReleaseInfo {
-    pub tag_name: String,
-    pub name: Option<String>,
-    pub date_utc: Option<DateTime<Utc>>,
-    pub is_prerelease: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CriteriaInfo {
-    pub max_days: i64,
-    pub min_contributors: usize,
-    pub min_commits: usize,
+/// Prints a single report in the configured format
+pub fn print_output(
+    config: &Config,
+    report: &RepositoryReport,
+) -> Result<()> {
+    match config.get_format() {
+        OutputFormat::Default => {
+            print_default_output(config, report);
+        }
+        OutputFormat::Json => {
+            print_json_output(report)?;
+        }
+        OutputFormat::GitHubActions => {
+            github_actions::print_github_actions_output(config, &[report])?;
+        }
+        OutputFormat::Sarif => {
+            sarif::print_sarif_output(&[(report, None)])?;
+        }
+        OutputFormat::Junit => {
+            junit::print_junit_output(None, &[report], &[])?;
+        }
+        OutputFormat::Ndjson => {
+            ndjson::print_ndjson_report(report)?;
+            ndjson::print_ndjson_summary(std::iter::once(Ok(report)))?;
+        }
+        OutputFormat::Field(field_name) => {
+            print_field_output(report, field_name)?;
+        }
+    }
+    Ok(())
 }
 
-pub fn create_repository_report(
+/// Called as soon as a repository of a batch run has been checked.
+/// Streaming formats print the result right away, the others wait for `print_batch_output`.
+pub fn print_batch_progress(
     config: &Config,
-    commits_count: usize,
-    contributors_count: usize,
-    open_prs: usize,
-    open_issues: usize,
-    last_commit: &CommitInfo,
-    last_release: Option<&ReleaseInfo>,
-    alive: bool,
-) -> RepositoryReport {
-    RepositoryReport {
-        owner: config.get_owner().to_string(),
-        repo: config.get_repo().to_string(),
-        commits_total: commits_count,
-        contributors_total: contributors_count,
-        open_pull_requests: open_prs,
-        open_issues,
-        last_commit: LastCommitInfo {
-            sha: last_commit.sha.clone(),
-            author_name: last_commit.commit.author.name.clone(),
-            author_email: last_commit.commit.author.email.clone(),
-            date_utc: last_commit.commit.author.date,
-            message: first_line(&last_commit.commit.message).to_string(),
-        },
-        last_release: last_release.map(|release| LastReleaseInfo {
-            tag_name: release.tag_name.clone(),
-            name: release.name.clone(),
-            date_utc: release.published_at,
-            is_prerelease: release.prerelease,
-        }),
-        project_alive: alive,
-        criteria: CriteriaInfo {
-            max_days: config.get_max_days(),
-            min_contributors: config.get_min_contributors(),
-            min_commits: config.get_min_commits(),
-        },
+    result: &BatchResult,
+) -> Result<()> {
+    if let OutputFormat::Ndjson = config.get_format() {
+        ndjson::print_ndjson_result(result)?;
     }
+    Ok(())
 }
 
-pub fn print_output(
+/// Prints the results of a batch run in the configured format
+pub fn print_batch_output(
     config: &Config,
-    report: &RepositoryReport,
+    results: &[BatchResult],
 ) -> Result<()> {
+    // Streamed results already include their errors
+    if !matches!(config.get_format(), OutputFormat::Ndjson) {
+        for result in results {
+            if let Err(e) = &result.report {
+                eprintln!("Error: {}: {e:#}", result.entry.target);
+            }
+        }
+    }
+
+    let checked: Vec<_> = results
+        .iter()
+        .filter_map(|result| result.report.as_ref().ok().map(|report| (report, Some(&result.entry))))
+        .collect();
+    let reports: Vec<&RepositoryReport> = checked.iter().map(|(report, _)| *report).collect();
+
     match config.get_format() {
         OutputFormat::Default => {
-            print_default_output(config, report);
+            for (i, report) in reports.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_default_output(config, report);
+            }
+            print_risk_ranking(&reports);
         }
         OutputFormat::Json => {
-            print_json_output(report)?;
+            let json = serde_json::to_string_pretty(&reports)?;
+            println!("{json}");
+        }
+        OutputFormat::GitHubActions => {
+            github_actions::print_github_actions_output(config, &reports)?;
+        }
+        OutputFormat::Sarif => {
+            sarif::print_sarif_output(&checked)?;
+        }
+        OutputFormat::Junit => {
+            let errors: Vec<_> = results
+                .iter()
+                .filter_map(|result| {
+                    result.report.as_ref().err().map(|error| junit::CheckError {
+                        name: result.entry.target.to_string(),
+                        error,
+                    })
+                })
+                .collect();
+            junit::print_junit_output(config.batch.as_deref(), &reports, &errors)?;
+        }
+        OutputFormat::Ndjson => {
+            // Results were already streamed, only the summary is left
+            ndjson::print_ndjson_summary(results.iter().map(|r| r.report.as_ref().map_err(|_| ())))?;
         }
         OutputFormat::Field(field_name) => {
-            print_field_output(report, field_name)?;
+            for report in &reports {
+                let value = extract_field_value(report, field_name)?;
+                println!("{}/{}: {value}", report.owner, report.repo);
+            }
         }
     }
     Ok(())
@@ -170,13 +222,87 @@ fn print_field_output(report: &RepositoryReport, field_name: &str) -> Result<()>
     Ok(())
 }
 
-fn print_default_output(config: &Config, report: &RepositoryReport) {
+/// Reports with a known risk, riskiest first
+pub(crate) fn risk_ranking<'a>(reports: &[&'a RepositoryReport]) -> Vec<&'a RepositoryReport> {
+    let mut ranked: Vec<_> = reports.iter().copied().filter(|report| report.risk.is_some()).collect();
+    ranked.sort_by(|a, b| b.risk.partial_cmp(&a.risk).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+fn print_risk_ranking(reports: &[&RepositoryReport]) {
+    let ranked = risk_ranking(reports);
+    if ranked.len() < 2 {
+        return;
+    }
+    println!();
+    println!("Risk ranking (importance × inactivity):");
+    for (i, report) in ranked.iter().enumerate() {
+        let stars = report.popularity.as_ref().map_or(0, |popularity| popularity.stars);
+        println!(
+            "{:>4}. {:.2}  {}/{} ({stars} stars, {})",
+            i + 1,
+            report.risk.unwrap_or_default(),
+            report.owner,
+            report.repo,
+            status_label(report)
+        );
+    }
+}
+
+/// Count for human-readable output, metrics without a value are shown as failed or unsupported
+pub(crate) fn display_count(report: &RepositoryReport, field: &str, count: Option<usize>) -> String {
+    match count {
+        Some(count) => count.to_string(),
+        None if report.errors.contains_key(field) => "error".to_string(),
+        None => "unsupported".to_string(),
+    }
+}
+
+/// Instant day-based values are shown relative to: when the report was scored
+pub(crate) fn reference_time(report: &RepositoryReport) -> chrono::DateTime<chrono::Utc> {
+    report.checked_at.unwrap_or_else(chrono::Utc::now)
+}
+
+/// Verdict shown for a report: an archived or disabled repository, or the score's verdict
+pub(crate) fn status_label(report: &RepositoryReport) -> String {
+    match report.verdict {
+        Some(verdict) => format!("{} ⛔", verdict.to_string().to_uppercase()),
+        None if report.project_alive => format!("ALIVE ✅{}", partial_note(report)),
+        None => format!("LIKELY DEAD ⚠️{}", partial_note(report)),
+    }
+}
+
+/// " (partial score: cadence failed)" when failed metrics count as 0 in the score, empty otherwise
+pub(crate) fn partial_note(report: &RepositoryReport) -> String {
+    match report.score.failed.as_slice() {
+        [] => String::new(),
+        failed => format!(" (partial score: {} failed)", failed.join(", ")),
+    }
+}
+
+/// Why a repository got its verdict, e.g. "the repository is archived"
+pub(crate) fn verdict_reason(report: &RepositoryReport, verdict: Verdict) -> String {
+    match (verdict, &report.license) {
+        (Verdict::LicenseDenied, Some(license)) if license.spdx_id == License::NONE => {
+            "the repository has no license".to_string()
+        }
+        (Verdict::LicenseDenied, Some(license)) => format!("the license {} is not allowed", license.spdx_id),
+        (Verdict::LicenseDenied, None) => "the license is unknown".to_string(),
+        _ => format!("the repository is {verdict}"),
+    }
+}
+
+pub(crate) fn days_since_last_commit(report: &RepositoryReport) -> i64 {
+    reference_time(report).signed_duration_since(report.last_commit.date_utc).num_days()
+}
+
+pub(crate) fn print_default_output(config: &Config, report: &RepositoryReport) {
     println!("Repo: {}/{}", report.owner, report.repo);
     println!("-------------------------------------------");
-    println!("Commits total            : {}", report.commits_total);
-    println!("Contributors total       : {}", report.contributors_total);
-    println!("Open pull requests       : {}", report.open_pull_requests);
-    println!("Open issues (unresolved) : {}", report.open_issues);
+    println!("Commits total            : {}", display_count(report, "commits_total", report.commits_total));
+    println!("Contributors total       : {}", display_count(report, "contributors_total", report.contributors_total));
+    println!("Open pull requests       : {}", display_count(report, "open_pull_requests", report.open_pull_requests));
+    println!("Open issues (unresolved) : {}", display_count(report, "open_issues", report.open_issues));
     println!("Last commit              :");
     println!("  sha                    : {}", report.last_commit.sha);
     println!(
@@ -193,7 +319,7 @@ fn print_default_output(config: &Config, report: &RepositoryReport) {
             println!("  name                   : {}", name);
         }
         if let Some(date) = release.date_utc {
-            let days_since_release = chrono::Utc::now().signed_duration_since(date).num_days();
+            let days_since_release = reference_time(report).signed_duration_since(date).num_days();
             println!("  date (UTC)             : {}", date);
             
             let release_status = if days_since_release <= config.get_max_release_days() {
@@ -215,31 +341,269 @@ fn print_default_output(config: &Config, report: &RepositoryReport) {
             println!("  status                 : Unknown age ❓");
         }
         println!("  prerelease             : {}", if release.is_prerelease { "Yes" } else { "No" });
+    } else if report.errors.contains_key("last_release") {
+        println!("Last release             : error");
     } else {
         println!("Last release             : No releases found");
     }
+
+    if let Some(releases) = &report.releases {
+        print_releases(config, releases);
+    } else if report.errors.contains_key("releases") {
+        println!("Release cadence          : error");
+    }
+
+    if let Some(pulls) = &report.pull_requests {
+        print_pull_requests(pulls);
+    } else if report.errors.contains_key("pull_requests") {
+        println!("Pull request handling    : error");
+    }
+
+    if let Some(issues) = &report.issues {
+        print_issues(issues);
+    } else if report.errors.contains_key("issues") {
+        println!("Issue triage             : error");
+    }
+
+    if let Some(ci) = &report.ci {
+        print_ci(ci);
+    } else if report.errors.contains_key("ci") {
+        println!("CI                       : error");
+    }
+
+    if let Some(security) = &report.security {
+        print_security(security);
+    } else if report.errors.contains_key("security") {
+        println!("Security                 : error");
+    }
+
+    if let Some(cadence) = &report.cadence {
+        print_cadence(config, cadence);
+    } else if report.errors.contains_key("cadence") {
+        println!("Commit cadence           : error");
+    }
+
+    if let Some(bus_factor) = &report.bus_factor {
+        print_bus_factor(config, bus_factor);
+    } else if report.errors.contains_key("bus_factor") {
+        println!("Bus factor               : error");
+    }
+
+    if let Some(popularity) = &report.popularity {
+        print_popularity(report, popularity);
+    } else if report.errors.contains_key("popularity") {
+        println!("Popularity               : error");
+    }
+
+    if let Some(license) = &report.license {
+        print_license(license);
+    } else if report.errors.contains_key("license") {
+        println!("License                  : error");
+    }
+
+    if let Some(community) = &report.community {
+        print_community(community);
+    } else if report.errors.contains_key("community") {
+        println!("Community profile        : error");
+    }
+
+    if let Some(metadata) = &report.metadata {
+        print_metadata(metadata);
+    }
+
+    if !report.errors.is_empty() {
+        println!("Failed to fetch          :");
+        for (field, error) in &report.errors {
+            println!("  {field:<22} : {error}");
+        }
+    }
     
     println!("-------------------------------------------");
-    println!(
-        "Project alive           : {}",
-        if report.project_alive { "ALIVE ✅" } else { "LIKELY DEAD ⚠️" }
-    );
+    println!("Project alive           : {}", status_label(report));
     println!(
         "Criteria: last ≤ {} days or (contributors ≥ {} and commits ≥ {})",
         config.get_max_days(), config.get_min_contributors(), config.get_min_commits()
     );
 }
 
+fn print_pull_requests(pulls: &PullRequestResponsiveness) {
+    let hours = |hours: Option<f64>| hours.map_or_else(|| "n/a".to_string(), |h| format!("{h:.1} hours"));
+    println!("Pull request handling    :");
+    println!("  opened last {:<3} days   : {}", pulls.window_days, pulls.opened);
+    println!("  first response (median): {}", hours(pulls.median_first_response_hours));
+    println!("  merge time (median)    : {}", hours(pulls.median_merge_hours));
+    match pulls.merged_share {
+        Some(share) => println!("  merged of closed       : {:.0}%", share * 100.0),
+        None => println!("  merged of closed       : n/a"),
+    }
+    let stale = if pulls.stale_open == 0 { "✅" } else { "⚠️" };
+    println!("  unanswered > {:<3} days  : {} {stale}", pulls.stale_days, pulls.stale_open);
+}
+
+fn print_releases(config: &Config, releases: &ReleaseCadence) {
+    let days = |days: Option<i64>| days.map_or_else(|| "none".to_string(), |d| format!("{d} days ago"));
+    match releases.source {
+        ReleaseSource::Releases => println!("Release cadence          :"),
+        ReleaseSource::Tags => println!("Release cadence (tags)   :"),
+    }
+    let regular = if releases.releases_365d >= config.get_min_releases() { "✅" } else { "⚠️" };
+    println!(
+        "  releases last 365 days : {} (+{} prereleases) {regular}",
+        releases.releases_365d, releases.prereleases_365d
+    );
+    match releases.median_interval_days {
+        Some(interval) => println!("  interval (median)      : {interval:.0} days"),
+        None => println!("  interval (median)      : n/a"),
+    }
+    println!("  last stable            : {}", days(releases.days_since_stable));
+    println!("  last prerelease        : {}", days(releases.days_since_prerelease));
+    println!(
+        "  major/minor/patch      : {} / {} / {}",
+        releases.major, releases.minor, releases.patch
+    );
+}
+
+fn print_issues(issues: &IssueHealth) {
+    let percent = |percent: Option<f64>| percent.map_or_else(|| "n/a".to_string(), |p| format!("{p:.0}%"));
+    println!("Issue triage             :");
+    match issues.median_first_response_hours {
+        Some(hours) => println!("  first response (median): {hours:.1} hours"),
+        None => println!("  first response (median): n/a"),
+    }
+    println!("  opened/closed 90 days  : {} / {}", issues.opened_90d, issues.closed_90d);
+    println!("  untouched > 180 days   : {}", percent(issues.untouched_percent));
+    println!("  labeled                : {}", percent(issues.labeled_percent));
+}
+
+fn print_ci(ci: &CiHealth) {
+    println!("CI                       :");
+    let head = match ci.head {
+        Some(CiState::Success) => "passing ✅",
+        Some(CiState::Failure) => "failing ❌",
+        Some(CiState::Pending) => "pending ⏳",
+        None => "no checks",
+    };
+    println!("  default branch head    : {head}");
+    match ci.pass_rate {
+        Some(rate) => println!("  pass rate              : {:.0}% of {} runs", rate * 100.0, ci.runs),
+        None => println!("  pass rate              : n/a"),
+    }
+    match ci.days_since_green {
+        Some(days) => println!("  last green run         : {days} days ago"),
+        None => println!("  last green run         : none"),
+    }
+}
+
+fn print_security(security: &SecurityPosture) {
+    let state = |state: SecurityState| match state {
+        SecurityState::Enabled => "enabled ✅",
+        SecurityState::Disabled => "disabled ⚠️",
+        SecurityState::Unknown => "unknown (no permission) ❓",
+    };
+    println!("Security                 :");
+    let policy = match security.security_policy {
+        SecurityState::Enabled => "Yes ✅",
+        SecurityState::Disabled => "No ⚠️",
+        SecurityState::Unknown => "unknown (no permission) ❓",
+    };
+    println!("  security policy        : {policy}");
+    match (security.advisories, security.advisories_365d) {
+        (Some(all), Some(last_year)) => println!("  advisories (last year) : {all} ({last_year})"),
+        _ => println!("  advisories (last year) : unknown (no permission) ❓"),
+    }
+    match security.median_days_to_fix {
+        Some(days) => println!("  time to fix (median)   : {days:.1} days"),
+        None => println!("  time to fix (median)   : n/a"),
+    }
+    println!("  vulnerability alerts   : {}", state(security.vulnerability_alerts));
+    println!("  Dependabot updates     : {}", state(security.dependabot_updates));
+    println!("  branch protection      : {}", state(security.branch_protection));
+}
+
+fn print_popularity(report: &RepositoryReport, popularity: &Popularity) {
+    println!("Popularity               :");
+    match (popularity.star_growth, popularity.star_growth_days) {
+        (Some(growth), Some(days)) => println!("  stars                  : {} ({growth:+} in {days} days)", popularity.stars),
+        (Some(growth), None) => println!("  stars                  : {} ({growth:+} since last run)", popularity.stars),
+        _ => println!("  stars                  : {}", popularity.stars),
+    }
+    println!("  forks                  : {}", popularity.forks);
+    println!("  watchers               : {}", display_count(report, "watchers", popularity.watchers));
+    println!("  dependents             : {}", display_count(report, "dependents", popularity.dependents));
+    if let Some(risk) = report.risk {
+        println!("  risk                   : {risk:.2} (importance × inactivity)");
+    }
+}
+
+fn print_license(license: &License) {
+    match (license.spdx_id.as_str(), &license.name) {
+        (License::NONE, _) => println!("License                  : none ⚠️"),
+        (License::NOASSERTION, Some(name)) => println!("License                  : {name} (unrecognized)"),
+        (spdx_id, _) => println!("License                  : {spdx_id}"),
+    }
+}
+
+fn print_community(community: &CommunityProfile) {
+    let present = |present: bool| if present { "Yes ✅" } else { "No ⚠️" };
+    println!("Community profile        : {}%", community.health_percentage);
+    println!("  README                 : {}", present(community.readme));
+    println!("  CONTRIBUTING           : {}", present(community.contributing));
+    println!("  CODE_OF_CONDUCT        : {}", present(community.code_of_conduct));
+}
+
+fn print_cadence(config: &Config, cadence: &Cadence) {
+    println!("Commit cadence           :");
+    println!(
+        "  commits 30/90/365 days : {} / {} / {}",
+        cadence.commits_30d, cadence.commits_90d, cadence.commits_365d
+    );
+    let steady = if cadence.active_weeks >= config.get_min_active_weeks() { "✅" } else { "⚠️" };
+    println!("  active weeks           : {} of 52 {steady}", cadence.active_weeks);
+    println!("  longest gap            : {} days", cadence.longest_gap_days);
+}
+
+fn print_bus_factor(config: &Config, bus_factor: &BusFactor) {
+    println!("Bus factor               :");
+    let shared = if bus_factor.committers_80 >= config.get_min_contributors() { "✅" } else { "⚠️" };
+    println!(
+        "  committers for 50/80%  : {} / {} {shared}",
+        bus_factor.committers_50, bus_factor.committers_80
+    );
+    println!("  top {TOP_CONTRIBUTORS} share            : {:.0}%", bus_factor.top_share * 100.0);
+    println!("  active 90/365 days     : {} / {}", bus_factor.active_90d, bus_factor.active_365d);
+}
+
+fn print_metadata(metadata: &RepositoryMetadata) {
+    println!("Repository               :");
+    println!("  archived               : {}", if metadata.archived { "Yes ⛔" } else { "No" });
+    if metadata.disabled {
+        println!("  disabled               : Yes ⛔");
+    }
+    match &metadata.parent {
+        Some(parent) => println!("  fork of                : {parent}"),
+        None if metadata.fork => println!("  fork                   : Yes"),
+        None => {}
+    }
+    if metadata.is_template {
+        println!("  template               : Yes");
+    }
+    if let Some(branch) = &metadata.default_branch {
+        println!("  default branch         : {branch}");
+    }
+    if let Some(pushed_at) = metadata.pushed_at {
+        println!("  pushed at (UTC)        : {pushed_at}");
+    }
+    if let Some(moved_to) = &metadata.moved_to {
+        println!("  moved to               : {moved_to} ➡️");
+    }
+}
+
 fn print_json_output(report: &RepositoryReport) -> Result<()> {
     let json = serde_json::to_string_pretty(report)?;
     println!("{json}");
     Ok(())
 }
 
-fn first_line(s: &str) -> &str {
-    s.lines().next().unwrap_or(s)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +612,10 @@ mod tests {
     fn test_output_format_from_str() {
         assert!(matches!(OutputFormat::from_str("default").unwrap(), OutputFormat::Default));
         assert!(matches!(OutputFormat::from_str("json").unwrap(), OutputFormat::Json));
+        assert!(matches!(OutputFormat::from_str("github-actions").unwrap(), OutputFormat::GitHubActions));
+        assert!(matches!(OutputFormat::from_str("sarif").unwrap(), OutputFormat::Sarif));
+        assert!(matches!(OutputFormat::from_str("junit").unwrap(), OutputFormat::Junit));
+        assert!(matches!(OutputFormat::from_str("ndjson").unwrap(), OutputFormat::Ndjson));
         
         if let OutputFormat::Field(field) = OutputFormat::from_str("field:commits_total").unwrap() {
             assert_eq!(field, "commits_total");
@@ -263,14 +631,10 @@ mod tests {
     fn test_output_format_display() {
         assert_eq!(OutputFormat::Default.to_string(), "default");
         assert_eq!(OutputFormat::Json.to_string(), "json");
+        assert_eq!(OutputFormat::GitHubActions.to_string(), "github-actions");
+        assert_eq!(OutputFormat::Sarif.to_string(), "sarif");
+        assert_eq!(OutputFormat::Junit.to_string(), "junit");
+        assert_eq!(OutputFormat::Ndjson.to_string(), "ndjson");
         assert_eq!(OutputFormat::Field("test".to_string()).to_string(), "field:test");
     }
-
-    #[test]
-    fn test_first_line() {
-        assert_eq!(first_line("single line"), "single line");
-        assert_eq!(first_line("first line\nsecond line"), "first line");
-        assert_eq!(first_line(""), "");
-        assert_eq!(first_line("line\n\n"), "line");
-    }
 }
\ No newline at end of file
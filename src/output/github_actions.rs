@@ -0,0 +1,352 @@
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use super::{
+    RepositoryReport, days_since_last_commit, display_count, partial_note, print_default_output, risk_ranking,
+    status_label, verdict_reason,
+};
+use crate::config::Config;
+use crate::report::SecurityState;
+use crate::scoring::Verdict;
+
+/// Prints reports for a GitHub Actions step: the regular human-readable output
+/// for the log, a workflow command annotation for each dead repository, a markdown
+/// job summary and step outputs. With several reports the outputs are aggregated:
+/// `alive` only when all repositories are alive, the lowest score and the largest
+/// number of days since the last commit.
+pub fn print_github_actions_output(config: &Config, reports: &[&RepositoryReport]) -> Result<()> {
+    let mut summary = String::new();
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_default_output(config, report);
+
+        let days = days_since_last_commit(report);
+        if let Some(command) = annotation(report, days) {
+            println!("{command}");
+        }
+        if let Some(command) = moved_notice(report) {
+            println!("{command}");
+        }
+        summary.push_str(&step_summary(config, report, days));
+    }
+    summary.push_str(&risk_summary(reports));
+
+    if let Ok(path) = std::env::var("GITHUB_STEP_SUMMARY") {
+        append_to_file(&path, &summary)
+            .context("Failed to write GitHub Actions step summary")?;
+    }
+
+    if let Ok(path) = std::env::var("GITHUB_OUTPUT") {
+        append_to_file(&path, &step_outputs(reports))
+            .context("Failed to write GitHub Actions step outputs")?;
+    }
+
+    Ok(())
+}
+
+/// Returns `::error` for archived, disabled and license-denied repositories and when there was
+/// no commit in the whole recency window, `::warning` for other likely dead
+/// repositories and nothing for alive ones.
+fn annotation(report: &RepositoryReport, days_since_last_commit: i64) -> Option<String> {
+    if report.project_alive {
+        return None;
+    }
+
+    if let Some(Verdict::LicenseDenied) = report.verdict {
+        let title = format!("{}/{} has a denied license", report.owner, report.repo);
+        let mut message = verdict_reason(report, Verdict::LicenseDenied);
+        message[..1].make_ascii_uppercase();
+        message.push_str(", replace the dependency");
+        return Some(format!("::error title={}::{}", escape_property(&title), escape_data(&message)));
+    }
+    if let Some(verdict) = report.verdict {
+        let title = format!("{}/{} is {verdict}", report.owner, report.repo);
+        let message = format!("The repository is {verdict} and no longer maintained, replace the dependency");
+        return Some(format!("::error title={}::{}", escape_property(&title), escape_data(&message)));
+    }
+
+    let level = if report.score.recency <= 0.0 { "error" } else { "warning" };
+    let title = format!("{}/{} is likely dead", report.owner, report.repo);
+    let message = format!(
+        "Last commit {} days ago, score {:.2}{} (criteria: last ≤ {} days or contributors ≥ {} and commits ≥ {})",
+        days_since_last_commit,
+        report.score.total,
+        partial_note(report),
+        report.criteria.max_days,
+        report.criteria.min_contributors,
+        report.criteria.min_commits,
+    );
+
+    Some(format!(
+        "::{level} title={}::{}",
+        escape_property(&title),
+        escape_data(&message)
+    ))
+}
+
+/// `::warning` for a renamed or transferred repository, pointing at its new location
+fn moved_notice(report: &RepositoryReport) -> Option<String> {
+    let moved_to = report.metadata.as_ref()?.moved_to.as_ref()?;
+    let title = format!("{}/{} moved", report.owner, report.repo);
+    let message = format!("The repository moved to {moved_to}, update the reference");
+    Some(format!("::warning title={}::{}", escape_property(&title), escape_data(&message)))
+}
+
+/// Markdown table of the repositories by risk, riskiest first; empty for fewer than two
+fn risk_summary(reports: &[&RepositoryReport]) -> String {
+    let ranked = risk_ranking(reports);
+    if ranked.len() < 2 {
+        return String::new();
+    }
+    let mut out = String::from("### Risk ranking (importance × inactivity)\n\n");
+    out.push_str("| # | Repository | Risk | Stars | Status |\n");
+    out.push_str("|---|------------|------|-------|--------|\n");
+    for (i, report) in ranked.iter().enumerate() {
+        out.push_str(&format!(
+            "| {} | {}/{} | {:.2} | {} | {} |\n",
+            i + 1,
+            report.owner,
+            report.repo,
+            report.risk.unwrap_or_default(),
+            report.popularity.as_ref().map_or(0, |popularity| popularity.stars),
+            status_label(report)
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+fn step_summary(config: &Config, report: &RepositoryReport, days_since_last_commit: i64) -> String {
+    let status = status_label(report);
+    let release = report
+        .last_release
+        .as_ref()
+        .map(|r| r.tag_name.clone())
+        .unwrap_or_else(|| "No releases found".to_string());
+
+    let mut out = String::new();
+    out.push_str(&format!("### {}/{}: {}\n\n", report.owner, report.repo, status));
+    out.push_str("| Metric | Value |\n");
+    out.push_str("|--------|-------|\n");
+    out.push_str(&format!("| Score | {:.2} |\n", report.score.total));
+    out.push_str(&format!("| Commits total | {} |\n", display_count(report, "commits_total", report.commits_total)));
+    out.push_str(&format!("| Contributors total | {} |\n", display_count(report, "contributors_total", report.contributors_total)));
+    out.push_str(&format!("| Open pull requests | {} |\n", display_count(report, "open_pull_requests", report.open_pull_requests)));
+    out.push_str(&format!("| Open issues | {} |\n", display_count(report, "open_issues", report.open_issues)));
+    out.push_str(&format!(
+        "| Last commit | `{}` ({} days ago) |\n",
+        short_sha(&report.last_commit.sha),
+        days_since_last_commit
+    ));
+    out.push_str(&format!("| Last release | {} |\n", release));
+    if let Some(releases) = &report.releases {
+        out.push_str(&format!(
+            "| Releases in the last year | {} ({} major, {} minor, {} patch) |\n",
+            releases.releases_365d, releases.major, releases.minor, releases.patch
+        ));
+    }
+    if let Some(pulls) = &report.pull_requests {
+        out.push_str(&format!(
+            "| Unanswered pull requests | {} older than {} days |\n",
+            pulls.stale_open, pulls.stale_days
+        ));
+    }
+    if let Some(issues) = &report.issues {
+        out.push_str(&format!(
+            "| Issues opened / closed | {} / {} in 90 days |\n",
+            issues.opened_90d, issues.closed_90d
+        ));
+    }
+    if let Some(ci) = &report.ci {
+        let pass_rate = ci.pass_rate.map_or_else(|| "n/a".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+        out.push_str(&format!("| CI pass rate | {pass_rate} of {} runs |\n", ci.runs));
+    }
+    if let Some(popularity) = &report.popularity {
+        out.push_str(&format!(
+            "| Stars / forks / dependents | {} / {} / {} |\n",
+            popularity.stars,
+            popularity.forks,
+            display_count(report, "dependents", popularity.dependents)
+        ));
+    }
+    if let Some(license) = &report.license {
+        out.push_str(&format!("| License | {} |\n", license.spdx_id));
+    }
+    if let Some(community) = &report.community {
+        out.push_str(&format!("| Community health | {}% |\n", community.health_percentage));
+    }
+    if let Some(security) = &report.security {
+        let policy = match security.security_policy {
+            SecurityState::Enabled => "present",
+            SecurityState::Disabled => "missing",
+            SecurityState::Unknown => "unknown",
+        };
+        let advisories = security.advisories.map_or_else(|| "unknown".to_string(), |count| count.to_string());
+        out.push_str(&format!(
+            "| Security | policy {policy}, {advisories} advisories, branch protection {} |\n",
+            security.branch_protection
+        ));
+    }
+    if let Some(cadence) = &report.cadence {
+        out.push_str(&format!(
+            "| Active weeks | {} of 52 ({} commits in 90 days) |\n",
+            cadence.active_weeks, cadence.commits_90d
+        ));
+    }
+    if let Some(bus_factor) = &report.bus_factor {
+        out.push_str(&format!(
+            "| Bus factor | {} committers for 80% of the last year's commits |\n",
+            bus_factor.committers_80
+        ));
+    }
+    if let Some(moved_to) = report.metadata.as_ref().and_then(|m| m.moved_to.as_ref()) {
+        out.push_str(&format!("| Moved to | {moved_to} |\n"));
+    }
+    out.push_str(&format!(
+        "\nCriteria: last ≤ {} days or (contributors ≥ {} and commits ≥ {})\n\n",
+        config.get_max_days(),
+        config.get_min_contributors(),
+        config.get_min_commits()
+    ));
+    out
+}
+
+fn step_outputs(reports: &[&RepositoryReport]) -> String {
+    let alive = reports.iter().all(|r| r.project_alive);
+    let score = reports.iter().map(|r| r.score.total).fold(f64::INFINITY, f64::min);
+    let days = reports.iter().map(|r| days_since_last_commit(r)).max().unwrap_or(0);
+    let score = if score.is_finite() { score } else { 0.0 };
+    format!("alive={alive}\nscore={score:.2}\ndays_since_last_commit={days}\n")
+}
+
+fn append_to_file(path: &str, content: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {path}"))?;
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("Failed to write {path}"))?;
+    Ok(())
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+// Workflow command escaping, see
+// https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{RepositoryMetadata, sample_report};
+    use crate::report::{License, Popularity};
+    use crate::scoring::ScoreBreakdown;
+    use chrono::Utc;
+
+    fn create_test_report(alive: bool, recency: f64) -> RepositoryReport {
+        RepositoryReport {
+            project_alive: alive,
+            score: ScoreBreakdown {
+                recency,
+                total: 0.3,
+                ..Default::default()
+            },
+            ..sample_report()
+        }
+    }
+
+    #[test]
+    fn test_annotation_level() {
+        assert!(annotation(&create_test_report(true, 1.0), 0).is_none());
+
+        let warning = annotation(&create_test_report(false, 0.2), 100).unwrap();
+        assert!(warning.starts_with("::warning title=test/repo is likely dead::Last commit 100 days ago"));
+
+        let error = annotation(&create_test_report(false, 0.0), 300).unwrap();
+        assert!(error.starts_with("::error "));
+
+        // Failed metrics are pointed out, they count against the score
+        let mut partial = create_test_report(false, 0.2);
+        partial.score.failed = vec!["cadence".to_string(), "releases".to_string()];
+        let warning = annotation(&partial, 100).unwrap();
+        assert!(warning.contains("score 0.30 (partial score: cadence, releases failed) (criteria"));
+        assert!(status_label(&partial).ends_with("(partial score: cadence, releases failed)"));
+
+        // An archived repository is an error however recent its last commit
+        let archived = RepositoryReport { verdict: Some(Verdict::Archived), ..create_test_report(false, 1.0) };
+        assert!(annotation(&archived, 1).unwrap().starts_with("::error title=test/repo is archived::"));
+        assert!(step_summary(&Config::default().with_defaults(), &archived, 1).contains("ARCHIVED ⛔"));
+
+        let unlicensed = RepositoryReport {
+            verdict: Some(Verdict::LicenseDenied),
+            license: Some(License::none()),
+            ..create_test_report(false, 1.0)
+        };
+        assert_eq!(
+            annotation(&unlicensed, 1).unwrap(),
+            "::error title=test/repo has a denied license::The repository has no license, replace the dependency"
+        );
+    }
+
+    #[test]
+    fn test_moved_notice() {
+        assert!(moved_notice(&create_test_report(true, 1.0)).is_none());
+        let moved = RepositoryReport {
+            metadata: Some(RepositoryMetadata { moved_to: Some("new/repo".to_string()), ..Default::default() }),
+            ..create_test_report(true, 1.0)
+        };
+        assert_eq!(
+            moved_notice(&moved).unwrap(),
+            "::warning title=test/repo moved::The repository moved to new/repo, update the reference"
+        );
+    }
+
+    #[test]
+    fn test_step_outputs() {
+        let mut dead = create_test_report(false, 0.0);
+        dead.last_commit.date_utc = Utc::now() - chrono::Duration::days(42);
+        assert_eq!(step_outputs(&[&dead]), "alive=false\nscore=0.30\ndays_since_last_commit=42\n");
+
+        let mut alive = create_test_report(true, 1.0);
+        alive.score.total = 0.9;
+        assert_eq!(step_outputs(&[&alive]), "alive=true\nscore=0.90\ndays_since_last_commit=0\n");
+        assert_eq!(
+            step_outputs(&[&alive, &dead]),
+            "alive=false\nscore=0.30\ndays_since_last_commit=42\n"
+        );
+    }
+
+    #[test]
+    fn test_risk_summary() {
+        let report = |repo: &str, risk: Option<f64>| RepositoryReport {
+            repo: repo.to_string(),
+            risk,
+            popularity: risk.map(|_| Popularity { stars: 900, ..Default::default() }),
+            ..create_test_report(false, 0.2)
+        };
+        let (low, high, unknown) = (report("low", Some(0.1)), report("high", Some(0.8)), report("unknown", None));
+        let summary = risk_summary(&[&low, &unknown, &high]);
+        assert!(summary.contains("| 1 | test/high | 0.80 | 900 | LIKELY DEAD ⚠️ |\n| 2 | test/low | 0.10 |"));
+        assert!(!summary.contains("unknown"));
+        // A single repository has nothing to rank
+        assert_eq!(risk_summary(&[&high, &unknown]), "");
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(escape_data("50%\nnext"), "50%25%0Anext");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+}
@@ -0,0 +1,210 @@
+use anyhow::Result;
+
+use super::{RepositoryReport, days_since_last_commit, display_count, partial_note, verdict_reason};
+use crate::scoring::Verdict;
+
+const SUITE_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// A repository that could not be checked, reported as a JUnit `<error>`
+pub struct CheckError<'a> {
+    pub name: String,
+    pub error: &'a anyhow::Error,
+}
+
+/// Prints a JUnit XML report with one test suite in which every repository is a
+/// test case and a likely dead repository is a failure.
+pub fn print_junit_output(suite_name: Option<&str>, reports: &[&RepositoryReport], errors: &[CheckError]) -> Result<()> {
+    println!("{}", junit_xml(suite_name.unwrap_or(SUITE_NAME), reports, errors));
+    Ok(())
+}
+
+fn junit_xml(suite_name: &str, reports: &[&RepositoryReport], errors: &[CheckError]) -> String {
+    let tests = reports.len() + errors.len();
+    let failures = reports.iter().filter(|r| !r.project_alive).count();
+    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S");
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{}\">\n",
+        escape(SUITE_NAME),
+        errors.len()
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{}\" timestamp=\"{timestamp}\">\n",
+        escape(suite_name),
+        errors.len()
+    ));
+
+    for report in reports {
+        let name = format!("{}/{}", report.owner, report.repo);
+        if report.project_alive {
+            out.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"/>\n",
+                escape(&report.owner),
+                escape(&name)
+            ));
+            continue;
+        }
+
+        out.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n",
+            escape(&report.owner),
+            escape(&name)
+        ));
+        out.push_str(&format!(
+            "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+            escape(&failure_message(report)),
+            failure_type(report),
+            escape(&failure_details(report))
+        ));
+        out.push_str("    </testcase>\n");
+    }
+
+    for error in errors {
+        let classname = error.name.split('/').next().unwrap_or(&error.name);
+        out.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n",
+            escape(classname),
+            escape(&error.name)
+        ));
+        out.push_str(&format!(
+            "      <error message=\"{}\" type=\"CheckError\">{}</error>\n",
+            escape(&error.error.to_string()),
+            escape(&format!("{:#}", error.error))
+        ));
+        out.push_str("    </testcase>\n");
+    }
+
+    out.push_str("  </testsuite>\n");
+    out.push_str("</testsuites>");
+    out
+}
+
+fn failure_type(report: &RepositoryReport) -> &'static str {
+    match report.verdict {
+        Some(Verdict::Archived) => "Archived",
+        Some(Verdict::Disabled) => "Disabled",
+        Some(Verdict::LicenseDenied) => "LicenseDenied",
+        None => "LikelyDead",
+    }
+}
+
+fn failure_message(report: &RepositoryReport) -> String {
+    if let Some(verdict) = report.verdict {
+        return format!("{}: {}", verdict.to_string().to_uppercase(), verdict_reason(report, verdict));
+    }
+    let score = &report.score;
+    format!(
+        "LIKELY DEAD: score {:.2}{} (recency {:.2}, commits {}, contributors {}, prs {}, issues {}, releases {:.2}, cadence {}, bus factor {}); \
+         criteria: last ≤ {} days or (contributors ≥ {} and commits ≥ {})",
+        score.total,
+        partial_note(report),
+        score.recency,
+        display_component(score.commits),
+        display_component(score.contributors),
+        display_component(score.prs),
+        display_component(score.issues),
+        score.releases,
+        display_component(score.cadence),
+        display_component(score.bus_factor),
+        report.criteria.max_days,
+        report.criteria.min_contributors,
+        report.criteria.min_commits,
+    )
+}
+
+fn failure_details(report: &RepositoryReport) -> String {
+    let release = match &report.last_release {
+        Some(release) => release.tag_name.clone(),
+        None => "No releases found".to_string(),
+    };
+    format!(
+        "Commits total      : {}\n\
+         Contributors total : {}\n\
+         Open pull requests : {}\n\
+         Open issues        : {}\n\
+         Last commit        : {} ({} days ago)\n\
+         Last release       : {}",
+        display_count(report, "commits_total", report.commits_total),
+        display_count(report, "contributors_total", report.contributors_total),
+        display_count(report, "open_pull_requests", report.open_pull_requests),
+        display_count(report, "open_issues", report.open_issues),
+        report.last_commit.date_utc,
+        days_since_last_commit(report),
+        release,
+    )
+}
+
+fn display_component(score: Option<f64>) -> String {
+    score.map_or_else(|| "n/a".to_string(), |s| format!("{s:.2}"))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{License, sample_report};
+    use crate::scoring::ScoreBreakdown;
+
+    fn create_test_report(repo: &str, alive: bool) -> RepositoryReport {
+        RepositoryReport {
+            repo: repo.to_string(),
+            project_alive: alive,
+            score: ScoreBreakdown {
+                total: 0.31,
+                commits: Some(1.0),
+                ..Default::default()
+            },
+            ..sample_report()
+        }
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let alive = create_test_report("alive", true);
+        let dead = create_test_report("dead", false);
+        let error = anyhow::anyhow!("Repository <missing> not found");
+        let errors = [CheckError {
+            name: "test/missing".to_string(),
+            error: &error,
+        }];
+
+        let xml = junit_xml("repos.txt", &[&alive, &dead], &errors);
+
+        assert!(xml.contains("<testsuite name=\"repos.txt\" tests=\"3\" failures=\"1\" errors=\"1\""));
+        assert!(xml.contains("<testcase classname=\"test\" name=\"test/alive\"/>"));
+        assert!(xml.contains("<failure message=\"LIKELY DEAD: score 0.31 (recency 0.00, commits 1.00, contributors n/a"));
+        assert!(xml.contains("criteria: last ≤ 60 days or (contributors ≥ 3 and commits ≥ 100)"));
+        assert!(xml.contains("<error message=\"Repository &lt;missing&gt; not found\""));
+
+        let archived = RepositoryReport { verdict: Some(Verdict::Archived), ..create_test_report("archived", false) };
+        let xml = junit_xml("repos.txt", &[&archived], &[]);
+        assert!(xml.contains("<failure message=\"ARCHIVED: the repository is archived\" type=\"Archived\">"));
+
+        let denied = RepositoryReport {
+            verdict: Some(Verdict::LicenseDenied),
+            license: Some(License { spdx_id: "AGPL-3.0-only".to_string(), name: None }),
+            ..create_test_report("denied", false)
+        };
+        let xml = junit_xml("repos.txt", &[&denied], &[]);
+        assert!(xml.contains(
+            "<failure message=\"LICENSE-DENIED: the license AGPL-3.0-only is not allowed\" type=\"LicenseDenied\">"
+        ));
+        let unknown = RepositoryReport { license: None, ..denied };
+        let xml = junit_xml("repos.txt", &[&unknown], &[]);
+        assert!(xml.contains("<failure message=\"LICENSE-DENIED: the license is unknown\" type=\"LicenseDenied\">"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a & <b> \"c\" 'd'"), "a &amp; &lt;b&gt; &quot;c&quot; &apos;d&apos;");
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use serde_json::json;
+use std::io::Write;
+
+use super::RepositoryReport;
+use crate::batch::BatchResult;
+
+/// Prints one compact JSON line for a finished repository: the report, or an
+/// error object when the repository could not be checked.
+pub fn print_ndjson_result(result: &BatchResult) -> Result<()> {
+    print_line(&result_line(result)?)
+}
+
+/// Prints a single report as an NDJSON line
+pub fn print_ndjson_report(report: &RepositoryReport) -> Result<()> {
+    print_line(&serde_json::to_string(report)?)
+}
+
+/// Prints the final summary line of a run
+pub fn print_ndjson_summary<'a>(reports: impl Iterator<Item = Result<&'a RepositoryReport, ()>>) -> Result<()> {
+    print_line(&summary_line(reports))
+}
+
+fn print_line(line: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{line}")?;
+    // Flush right away so consumers see every repository as soon as it finishes
+    stdout.flush()?;
+    Ok(())
+}
+
+fn result_line(result: &BatchResult) -> Result<String> {
+    let line = match &result.report {
+        Ok(report) => serde_json::to_string(report)?,
+        Err(e) => json!({
+            "owner": result.entry.target.owner,
+            "repo": result.entry.target.repo,
+            "error": format!("{e:#}"),
+        })
+        .to_string(),
+    };
+    Ok(line)
+}
+
+fn summary_line<'a>(reports: impl Iterator<Item = Result<&'a RepositoryReport, ()>>) -> String {
+    let (mut total, mut alive, mut dead, mut errors) = (0, 0, 0, 0);
+    for report in reports {
+        total += 1;
+        match report {
+            Ok(report) if report.project_alive => alive += 1,
+            Ok(_) => dead += 1,
+            Err(()) => errors += 1,
+        }
+    }
+    json!({
+        "summary": {
+            "total": total,
+            "alive": alive,
+            "dead": dead,
+            "errors": errors,
+        }
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::BatchEntry;
+    use crate::forge::RepoTarget;
+    use crate::report::sample_report;
+
+    fn create_test_report(alive: bool) -> RepositoryReport {
+        RepositoryReport {
+            project_alive: alive,
+            ..sample_report()
+        }
+    }
+
+    #[test]
+    fn test_result_line() {
+        let entry = BatchEntry {
+            target: RepoTarget::github("test", "missing"),
+            source: "repos.txt".to_string(),
+            line: 1,
+        };
+        let result = BatchResult {
+            entry: entry.clone(),
+            report: Ok(create_test_report(true)),
+        };
+        let line = result_line(&result).unwrap();
+        assert!(!line.contains('\n'));
+        assert!(line.contains("\"project_alive\":true"));
+
+        let result = BatchResult {
+            entry,
+            report: Err(anyhow::anyhow!("not found")),
+        };
+        assert_eq!(
+            result_line(&result).unwrap(),
+            r#"{"error":"not found","owner":"test","repo":"missing"}"#
+        );
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let alive = create_test_report(true);
+        let dead = create_test_report(false);
+        let line = summary_line([Ok(&alive), Ok(&dead), Ok(&alive), Err(())].into_iter());
+        assert_eq!(line, r#"{"summary":{"alive":2,"dead":1,"errors":1,"total":4}}"#);
+    }
+}
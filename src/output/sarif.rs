@@ -0,0 +1,252 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use super::{RepositoryReport, days_since_last_commit, verdict_reason};
+use crate::batch::BatchEntry;
+use crate::scoring::Verdict;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/jozef-pridavok/github-activity-check";
+
+/// Score below which an unmaintained dependency is reported as an error instead of a warning
+const ERROR_SCORE: f64 = 0.25;
+
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        id: "archived",
+        name: "ArchivedRepository",
+        description: "The repository is archived and no longer maintained.",
+    },
+    Rule {
+        id: "disabled",
+        name: "DisabledRepository",
+        description: "The repository is disabled by the forge and can't be used.",
+    },
+    Rule {
+        id: "license-denied",
+        name: "DeniedLicense",
+        description: "The repository has no license or one the license policy doesn't allow.",
+    },
+    Rule {
+        id: "stale-commits",
+        name: "StaleCommits",
+        description: "The repository has no commits within the configured number of days.",
+    },
+    Rule {
+        id: "no-release",
+        name: "NoRecentRelease",
+        description: "The repository has no release within the configured number of days.",
+    },
+    Rule {
+        id: "single-maintainer",
+        name: "SingleMaintainer",
+        description: "The repository depends on a single contributor.",
+    },
+    Rule {
+        id: "low-activity",
+        name: "LowActivity",
+        description: "The overall activity score of the repository is below the threshold.",
+    },
+];
+
+/// Prints a SARIF 2.1.0 log with one result per likely dead repository.
+/// Repositories coming from a batch file are located at the line declaring them.
+pub fn print_sarif_output(items: &[(&RepositoryReport, Option<&BatchEntry>)]) -> Result<()> {
+    let json = serde_json::to_string_pretty(&sarif_log(items))?;
+    println!("{json}");
+    Ok(())
+}
+
+fn sarif_log(items: &[(&RepositoryReport, Option<&BatchEntry>)]) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "name": rule.name,
+                "shortDescription": { "text": rule.description },
+                "defaultConfiguration": { "level": "warning" },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = items
+        .iter()
+        .filter(|(report, _)| !report.project_alive)
+        .map(|(report, entry)| sarif_result(report, *entry))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+fn sarif_result(report: &RepositoryReport, entry: Option<&BatchEntry>) -> Value {
+    let days = days_since_last_commit(report);
+    let rule_id = rule_for(report, days);
+    let rule_index = RULES.iter().position(|rule| rule.id == rule_id).expect("rule_for picks ids from RULES");
+    let full_name = format!("{}/{}", report.owner, report.repo);
+
+    let mut location = json!({
+        "logicalLocations": [{ "fullyQualifiedName": full_name, "kind": "module" }],
+    });
+    if let Some(entry) = entry {
+        location["physicalLocation"] = json!({
+            "artifactLocation": { "uri": entry.source.replace('\\', "/") },
+            "region": { "startLine": entry.line },
+        });
+    }
+
+    let (level, text) = match report.verdict {
+        Some(Verdict::LicenseDenied) => ("error", format!("{full_name}: {}", verdict_reason(report, Verdict::LicenseDenied))),
+        Some(verdict) => ("error", format!("{full_name} is {verdict}: last commit {days} days ago")),
+        None => (
+            level_for(report.score.total),
+            format!(
+                "{full_name} is likely unmaintained: last commit {days} days ago, score {:.2}",
+                report.score.total
+            ),
+        ),
+    };
+
+    json!({
+        "ruleId": rule_id,
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": { "text": text },
+        "locations": [location],
+        "partialFingerprints": { "repository": full_name },
+        "properties": {
+            "score": report.score,
+            "commits_total": report.commits_total,
+            "contributors_total": report.contributors_total,
+            "days_since_last_commit": days,
+            "verdict": report.verdict,
+        },
+    })
+}
+
+/// Picks the most specific reason a repository is considered dead
+fn rule_for(report: &RepositoryReport, days_since_last_commit: i64) -> &'static str {
+    if let Some(verdict) = report.verdict {
+        match verdict {
+            Verdict::Archived => "archived",
+            Verdict::Disabled => "disabled",
+            Verdict::LicenseDenied => "license-denied",
+        }
+    } else if days_since_last_commit > report.criteria.max_days {
+        "stale-commits"
+    } else if report.score.releases <= 0.0 {
+        "no-release"
+    } else if report.contributors_total.is_some_and(|c| c <= 1) {
+        "single-maintainer"
+    } else {
+        "low-activity"
+    }
+}
+
+fn level_for(score: f64) -> &'static str {
+    if score < ERROR_SCORE { "error" } else { "warning" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::RepoTarget;
+    use crate::report::sample_report;
+    use crate::scoring::ScoreBreakdown;
+    use chrono::{Duration, Utc};
+
+    fn create_test_report(alive: bool, days_ago: i64, total: f64) -> RepositoryReport {
+        let mut report = RepositoryReport {
+            contributors_total: Some(1),
+            project_alive: alive,
+            score: ScoreBreakdown {
+                total,
+                ..Default::default()
+            },
+            ..sample_report()
+        };
+        report.last_commit.date_utc = Utc::now() - Duration::days(days_ago);
+        report
+    }
+
+    #[test]
+    fn test_rule_selection() {
+        assert_eq!(rule_for(&create_test_report(false, 100, 0.2), 100), "stale-commits");
+        assert_eq!(rule_for(&create_test_report(false, 10, 0.4), 10), "no-release");
+
+        let archived = RepositoryReport { verdict: Some(Verdict::Archived), ..create_test_report(false, 10, 0.9) };
+        assert_eq!(rule_for(&archived, 10), "archived");
+        let result = sarif_result(&archived, None);
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(result["properties"]["verdict"], "archived");
+    }
+
+    #[test]
+    fn test_every_rule_is_declared() {
+        let mut dead = create_test_report(false, 10, 0.4);
+        dead.score.releases = 1.0;
+        let picked: Vec<&str> = [
+            RepositoryReport { verdict: Some(Verdict::Archived), ..dead.clone() },
+            RepositoryReport { verdict: Some(Verdict::Disabled), ..dead.clone() },
+            RepositoryReport { verdict: Some(Verdict::LicenseDenied), ..dead.clone() },
+            create_test_report(false, 100, 0.2),
+            create_test_report(false, 10, 0.4),
+            dead.clone(),
+            RepositoryReport { contributors_total: Some(5), ..dead },
+        ]
+        .iter()
+        .map(|report| {
+            // Panics for a rule missing from RULES
+            let result = sarif_result(report, None);
+            RULES[result["ruleIndex"].as_u64().unwrap() as usize].id
+        })
+        .collect();
+        let declared: Vec<&str> = RULES.iter().map(|rule| rule.id).collect();
+        assert_eq!(picked, declared);
+    }
+
+    #[test]
+    fn test_sarif_log_results_and_locations() {
+        let alive = create_test_report(true, 1, 0.9);
+        let dead = create_test_report(false, 200, 0.1);
+        let entry = BatchEntry {
+            target: RepoTarget::github("test", "repo"),
+            source: "deps\\repos.txt".to_string(),
+            line: 7,
+        };
+
+        let log = sarif_log(&[(&alive, None), (&dead, Some(&entry))]);
+        assert_eq!(log["version"], "2.1.0");
+
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "stale-commits");
+        assert_eq!(results[0]["level"], "error");
+
+        let location = &results[0]["locations"][0];
+        assert_eq!(location["physicalLocation"]["artifactLocation"]["uri"], "deps/repos.txt");
+        assert_eq!(location["physicalLocation"]["region"]["startLine"], 7);
+        assert_eq!(location["logicalLocations"][0]["fullyQualifiedName"], "test/repo");
+    }
+}
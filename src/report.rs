@@ -0,0 +1,454 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::config::Config;
+use crate::forge::RepositoryData;
+use crate::scoring::{ScoreBreakdown, Verdict};
+
+/// Everything known about a repository after a check, also stored in history files.
+/// Counts are `None` when the forge doesn't provide them (see `unsupported`)
+/// or they failed to be fetched (see `errors`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryReport {
+    pub owner: String,
+    pub repo: String,
+    pub commits_total: Option<usize>,
+    pub contributors_total: Option<usize>,
+    pub open_pull_requests: Option<usize>,
+    pub open_issues: Option<usize>,
+    /// Commit activity in the last year, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cadence: Option<Cadence>,
+    /// Distribution of the last year's commits over committers, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bus_factor: Option<BusFactor>,
+    /// Pull request handling, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_requests: Option<PullRequestResponsiveness>,
+    /// Issue triage, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issues: Option<IssueHealth>,
+    /// Release history, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub releases: Option<ReleaseCadence>,
+    /// CI results of the default branch, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci: Option<CiHealth>,
+    /// Security policy, advisories and settings, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecurityPosture>,
+    /// Community health files, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub community: Option<CommunityProfile>,
+    /// Detected license, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+    /// Stars, forks, watchers and dependents, `None` when unsupported or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub popularity: Option<Popularity>,
+    pub last_commit: LastCommitInfo,
+    pub last_release: Option<LastReleaseInfo>,
+    pub project_alive: bool,
+    #[serde(default)]
+    pub score: ScoreBreakdown,
+    pub criteria: CriteriaInfo,
+    /// Repository properties, only present for forges that report them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RepositoryMetadata>,
+    /// Verdict overriding the score, e.g. for an archived repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+    /// Importance × inactivity (0-1) for ranking batch results, `None` without popularity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<f64>,
+    /// Report fields the forge has no data for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unsupported: Vec<String>,
+    /// Report fields that failed to be fetched, with the error; their score components count as 0, see `score.failed`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, String>,
+    /// Instant the report was scored at; day-based values are relative to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastCommitInfo {
+    pub sha: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub date_utc: DateTime<Utc>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastReleaseInfo {
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub date_utc: Option<DateTime<Utc>>,
+    pub is_prerelease: bool,
+}
+
+/// Commit activity over rolling windows ending at the check time
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cadence {
+    pub commits_30d: usize,
+    pub commits_90d: usize,
+    pub commits_365d: usize,
+    /// Weeks of the last 52 with at least one commit
+    pub active_weeks: usize,
+    /// Longest stretch without commits in the last 365 days, in days; the
+    /// window start and the check time count as stretch boundaries
+    pub longest_gap_days: i64,
+}
+
+/// How concentrated the last year's commits are on a few people
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BusFactor {
+    /// Share of the last 365 days' commits by the [`TOP_CONTRIBUTORS`](crate::types::TOP_CONTRIBUTORS)
+    /// most active committers, 0.0 without commits
+    pub top_share: f64,
+    /// Fewest committers accounting for half of the last 365 days' commits
+    pub committers_50: usize,
+    /// Fewest committers accounting for 80% of the last 365 days' commits
+    pub committers_80: usize,
+    /// Distinct committers in the last 90 days
+    pub active_90d: usize,
+    /// Distinct committers in the last 365 days
+    pub active_365d: usize,
+}
+
+/// How pull requests opened in the last `window_days` were handled; durations
+/// are medians in hours
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PullRequestResponsiveness {
+    pub window_days: i64,
+    /// Pull requests opened in the window
+    pub opened: usize,
+    /// Time to the first maintainer comment or review, of the pull requests that got one;
+    /// `None` when the forge doesn't report response times
+    pub median_first_response_hours: Option<f64>,
+    pub median_merge_hours: Option<f64>,
+    /// Merged share of the closed pull requests, `None` when none was closed
+    pub merged_share: Option<f64>,
+    pub stale_days: i64,
+    /// Open pull requests older than `stale_days` without a maintainer response
+    pub stale_open: usize,
+}
+
+/// How issues are triaged; shares are percentages so `--check` can compare them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IssueHealth {
+    /// Time to the first maintainer comment on issues opened in the last 90 days, of the
+    /// issues that got one; `None` when the forge doesn't report response times
+    pub median_first_response_hours: Option<f64>,
+    pub opened_90d: usize,
+    pub closed_90d: usize,
+    /// Open issues without any activity for over 180 days, `None` without open issues
+    pub untouched_percent: Option<f64>,
+    /// Open issues with at least one label, `None` without open issues
+    pub labeled_percent: Option<f64>,
+}
+
+/// Where a [`ReleaseCadence`] comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseSource {
+    /// Releases published on the forge
+    #[default]
+    Releases,
+    /// Version tags of a project that publishes no releases
+    Tags,
+}
+
+/// Releases over the last 365 days; intervals and ages are in days
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseCadence {
+    pub source: ReleaseSource,
+    /// Stable releases in the last 365 days
+    pub releases_365d: usize,
+    pub prereleases_365d: usize,
+    /// Between consecutive stable releases, of the intervals ending in the last 365 days
+    pub median_interval_days: Option<f64>,
+    pub days_since_stable: Option<i64>,
+    pub days_since_prerelease: Option<i64>,
+    /// Stable releases of the last 365 days by semver bump over the previous
+    /// lower version; tags that aren't versions are left out
+    pub major: usize,
+    pub minor: usize,
+    pub patch: usize,
+}
+
+/// Combined state of the commit statuses and check runs of a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CiState {
+    Success,
+    Failure,
+    Pending,
+}
+
+/// CI results of the default branch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CiHealth {
+    /// State of the branch head, `None` when it has no statuses or checks
+    pub head: Option<CiState>,
+    /// Finished workflow runs the pass rate covers, at most `ci_runs`
+    pub runs: usize,
+    /// Passed share of those runs, cancelled and skipped ones left out
+    pub pass_rate: Option<f64>,
+    /// Days since the last passed run, `None` without one
+    pub days_since_green: Option<i64>,
+}
+
+/// Whether a security setting is on, `unknown` when the token isn't allowed to read it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecurityState {
+    Enabled,
+    Disabled,
+    #[default]
+    Unknown,
+}
+
+impl std::fmt::Display for SecurityState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SecurityState::Enabled => "enabled",
+            SecurityState::Disabled => "disabled",
+            SecurityState::Unknown => "unknown",
+        })
+    }
+}
+
+/// Security signals of a repository for supply chain reviews
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecurityPosture {
+    /// `SECURITY.md` in the root, `.github` or `docs` directory
+    pub security_policy: SecurityState,
+    /// Published repository security advisories, `None` when the token can't list them
+    pub advisories: Option<usize>,
+    pub advisories_365d: Option<usize>,
+    /// Median days from an advisory's creation to its publication with the fix
+    pub median_days_to_fix: Option<f64>,
+    /// Dependabot alerts for vulnerable dependencies, readable by admins only
+    pub vulnerability_alerts: SecurityState,
+    /// Dependabot version updates configured in `.github/dependabot.yml`
+    pub dependabot_updates: SecurityState,
+    /// Protection rules on the default branch
+    pub branch_protection: SecurityState,
+}
+
+/// How widely a repository is used
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Popularity {
+    pub stars: usize,
+    pub forks: usize,
+    /// Accounts watching the repository, `None` when the forge doesn't count them
+    pub watchers: Option<usize>,
+    /// Repositories depending on it, `None` when unsupported or failed
+    pub dependents: Option<usize>,
+    /// Stars gained since the previous history snapshot, negative when lost
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub star_growth: Option<i64>,
+    /// Days between the previous history snapshot and this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub star_growth_days: Option<i64>,
+}
+
+/// Community health files of a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommunityProfile {
+    /// Share of the recommended community files present, in percent
+    pub health_percentage: u8,
+    pub readme: bool,
+    pub contributing: bool,
+    pub code_of_conduct: bool,
+}
+
+/// License of a repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct License {
+    /// SPDX identifier, `NONE` without a license and `NOASSERTION` when it isn't recognized
+    pub spdx_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl License {
+    /// SPDX identifier of a repository without a license
+    pub const NONE: &str = "NONE";
+    /// SPDX identifier of an unrecognized license
+    pub const NOASSERTION: &str = "NOASSERTION";
+
+    /// License of a repository that has none
+    pub fn none() -> Self {
+        License { spdx_id: Self::NONE.to_string(), name: None }
+    }
+}
+
+/// Properties of the repository itself, as opposed to its activity
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryMetadata {
+    pub archived: bool,
+    pub fork: bool,
+    pub disabled: bool,
+    /// `owner/repo` the repository was forked from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub is_template: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// Last push to any branch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pushed_at: Option<DateTime<Utc>>,
+    /// Current `owner/repo` of a renamed or transferred repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<String>,
+}
+
+impl RepositoryMetadata {
+    /// `full_name` the forge answered with when it isn't the requested `owner/repo`;
+    /// forges redirect requests for renamed and transferred repositories
+    pub(crate) fn moved_to(owner: &str, repo: &str, full_name: &str) -> Option<String> {
+        (!full_name.eq_ignore_ascii_case(&format!("{owner}/{repo}"))).then(|| full_name.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriteriaInfo {
+    pub max_days: i64,
+    pub min_contributors: usize,
+    pub min_commits: usize,
+}
+
+/// Unscored report of fetched data without the time-dependent sections; `config` must have owner, repo and defaults
+pub fn create_repository_report(config: &Config, data: &RepositoryData) -> RepositoryReport {
+    let last_commit = &data.last_commit;
+    RepositoryReport {
+        owner: config.get_owner().to_string(),
+        repo: config.get_repo().to_string(),
+        commits_total: data.commits_total,
+        contributors_total: data.contributors_total,
+        open_pull_requests: data.open_pull_requests,
+        open_issues: data.open_issues,
+        cadence: None,
+        bus_factor: None,
+        pull_requests: None,
+        issues: None,
+        releases: None,
+        ci: None,
+        security: None,
+        community: data.community.clone(),
+        license: data.license.clone(),
+        popularity: data.popularity.clone().map(|popularity| Popularity { dependents: data.dependents, ..popularity }),
+        last_commit: LastCommitInfo {
+            sha: last_commit.sha.clone(),
+            author_name: last_commit.commit.author.name.clone(),
+            author_email: last_commit.commit.author.email.clone(),
+            date_utc: last_commit.commit.author.date,
+            message: first_line(&last_commit.commit.message).to_string(),
+        },
+        last_release: data.latest_release.as_ref().map(|release| LastReleaseInfo {
+            tag_name: release.tag_name.clone(),
+            name: release.name.clone(),
+            date_utc: release.published_at,
+            is_prerelease: release.prerelease,
+        }),
+        project_alive: false,
+        score: ScoreBreakdown::default(),
+        criteria: CriteriaInfo {
+            max_days: config.get_max_days(),
+            min_contributors: config.get_min_contributors(),
+            min_commits: config.get_min_commits(),
+        },
+        metadata: data.metadata.clone(),
+        verdict: None,
+        risk: None,
+        unsupported: data.unsupported.clone(),
+        errors: data.errors.clone(),
+        checked_at: None,
+    }
+}
+
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or(s)
+}
+
+/// Report with fixed values for tests, override fields with struct update syntax
+#[cfg(test)]
+pub(crate) fn sample_report() -> RepositoryReport {
+    RepositoryReport {
+        owner: "test".to_string(),
+        repo: "repo".to_string(),
+        commits_total: Some(100),
+        contributors_total: Some(10),
+        open_pull_requests: Some(5),
+        open_issues: Some(20),
+        cadence: None,
+        bus_factor: None,
+        pull_requests: None,
+        issues: None,
+        releases: None,
+        ci: None,
+        security: None,
+        community: None,
+        license: None,
+        popularity: None,
+        last_commit: LastCommitInfo {
+            sha: "abc1234567".to_string(),
+            author_name: "author".to_string(),
+            author_email: "author@test.com".to_string(),
+            date_utc: Utc::now(),
+            message: "test commit".to_string(),
+        },
+        last_release: None,
+        project_alive: true,
+        score: ScoreBreakdown::default(),
+        criteria: CriteriaInfo {
+            max_days: 60,
+            min_contributors: 3,
+            min_commits: 100,
+        },
+        metadata: None,
+        verdict: None,
+        risk: None,
+        unsupported: Vec::new(),
+        errors: BTreeMap::new(),
+        checked_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_line() {
+        assert_eq!(first_line("single line"), "single line");
+        assert_eq!(first_line("first line\nsecond line"), "first line");
+        assert_eq!(first_line(""), "");
+        assert_eq!(first_line("line\n\n"), "line");
+    }
+
+    #[test]
+    fn test_unsupported_counts_roundtrip() {
+        let report = RepositoryReport {
+            commits_total: None,
+            unsupported: vec!["commits_total".to_string()],
+            ..sample_report()
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json["commits_total"].is_null());
+        assert_eq!(json["unsupported"][0], "commits_total");
+
+        // Reports saved before `unsupported` existed still load
+        let mut old = serde_json::to_value(sample_report()).unwrap();
+        old.as_object_mut().unwrap().remove("unsupported");
+        let loaded: RepositoryReport = serde_json::from_value(old).unwrap();
+        assert!(loaded.unsupported.is_empty());
+        assert_eq!(loaded.commits_total, Some(100));
+    }
+}
@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::clock::{Clock, SystemClock};
 use crate::config::Config;
-use crate::types::ReleaseInfo;
+use crate::report::{CiHealth, CiState, IssueHealth, Popularity, PullRequestResponsiveness, ReleaseCadence, RepositoryReport};
+use crate::types::ACTIVITY_DAYS;
 
 // Scoring weights - could be made configurable in the future
 pub struct ScoringWeights {
@@ -10,6 +13,10 @@ pub struct ScoringWeights {
     pub prs: f64,
     pub issues: f64,
     pub releases: f64,
+    pub cadence: f64,
+    pub bus_factor: f64,
+    /// Only with `score_ci`
+    pub ci: f64,
 }
 
 impl Default for ScoringWeights {
@@ -21,6 +28,9 @@ impl Default for ScoringWeights {
             prs: 0.10,
             issues: 0.10,
             releases: 0.15,
+            cadence: 0.15,
+            bus_factor: 0.10,
+            ci: 0.10,
         }
     }
 }
@@ -29,6 +39,10 @@ pub struct ScoringThresholds {
     pub activity_threshold: f64,
     pub recency_threshold: f64,
     pub recency_scale_multiplier: f64,
+    /// Stars that make a repository fully important for the risk ranking
+    pub important_stars: f64,
+    /// Dependents that make a repository fully important for the risk ranking
+    pub important_dependents: f64,
 }
 
 impl Default for ScoringThresholds {
@@ -37,53 +51,206 @@ impl Default for ScoringThresholds {
             activity_threshold: 0.45,
             recency_threshold: 0.8,
             recency_scale_multiplier: 2.0,
+            important_stars: 10_000.0,
+            important_dependents: 1_000.0,
         }
     }
 }
 
-#[derive(Default)]
+/// Normalized component scores (0.0 - 1.0) and their weighted total.
+/// Components the forge has no data for are `None` and left out of the total
+/// (`releases` is 0 then). Components that failed to be fetched count as 0 and
+/// are listed in `failed`, the total is partial then.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub recency: f64,
+    pub commits: Option<f64>,
+    pub contributors: Option<f64>,
+    pub prs: Option<f64>,
+    pub issues: Option<f64>,
+    pub releases: f64,
+    #[serde(default)]
+    pub cadence: Option<f64>,
+    #[serde(default)]
+    pub bus_factor: Option<f64>,
+    /// `None` unless `score_ci` is set
+    #[serde(default)]
+    pub ci: Option<f64>,
+    pub total: f64,
+    /// Components whose metrics failed to be fetched, e.g. `cadence`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+}
+
+/// Verdict that overrides the activity score: such a repository fails the
+/// check however recent its last commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// Archived by its owner, read-only
+    Archived,
+    /// Disabled by the forge, e.g. for a terms of service violation
+    Disabled,
+    /// No license, or one the `license_allow`/`license_deny` policy rejects
+    #[serde(rename = "license-denied")]
+    LicenseDenied,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Verdict::Archived => "archived",
+            Verdict::Disabled => "disabled",
+            Verdict::LicenseDenied => "license-denied",
+        })
+    }
+}
+
+/// Decides whether a project is alive from a weighted combination of normalized metrics
 pub struct ProjectScorer {
     weights: ScoringWeights,
     thresholds: ScoringThresholds,
+    clock: Box<dyn Clock>,
 }
 
+impl Default for ProjectScorer {
+    fn default() -> Self {
+        Self {
+            weights: ScoringWeights::default(),
+            thresholds: ScoringThresholds::default(),
+            clock: Box::new(SystemClock),
+        }
+    }
+}
 
 impl ProjectScorer {
     pub fn new() -> Self {
         Self::default()
     }
 
-    pub fn is_project_alive(
-        &self,
-        last_commit_date: &DateTime<Utc>,
-        commits: usize,
-        contributors: usize,
-        open_prs: usize,
-        open_issues: usize,
-        latest_release: Option<&ReleaseInfo>,
-        config: &Config,
-    ) -> bool {
-        let days_since = (Utc::now() - *last_commit_date).num_days() as f64;
+    /// Current time of the scorer's clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Scores relative to `clock` instead of the system time
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Final rule: alive if weighted score >= threshold OR recency is strong (recent commit)
+    pub fn is_alive(&self, score: &ScoreBreakdown) -> bool {
+        score.total >= self.thresholds.activity_threshold || score.recency >= self.thresholds.recency_threshold
+    }
+
+    /// Hard verdict from the repository metadata, `None` when the score decides
+    pub fn verdict(&self, report: &RepositoryReport) -> Option<Verdict> {
+        let metadata = report.metadata.as_ref()?;
+        if metadata.disabled {
+            Some(Verdict::Disabled)
+        } else if metadata.archived {
+            Some(Verdict::Archived)
+        } else {
+            None
+        }
+    }
+
+    /// Scores the report as of the clock's current time and records the time,
+    /// score and verdict in it
+    pub fn evaluate(&self, report: &mut RepositoryReport, config: &Config) {
+        self.evaluate_at(report, config, self.clock.now());
+    }
+
+    /// Like [`evaluate`](Self::evaluate), as of `now`
+    pub fn evaluate_at(&self, report: &mut RepositoryReport, config: &Config, now: DateTime<Utc>) {
+        report.checked_at = Some(now);
+        report.score = self.score_at(report, config, now);
+        // A license that couldn't be fetched can't pass a policy, one the forge
+        // doesn't report stays unknown
+        report.verdict = self.verdict(report).or_else(|| {
+            let allowed = match &report.license {
+                Some(license) => config.license_allowed(&license.spdx_id),
+                None => !config.has_license_policy() || report.unsupported.iter().any(|field| field == "license"),
+            };
+            (!allowed).then_some(Verdict::LicenseDenied)
+        });
+        report.project_alive = report.verdict.is_none() && self.is_alive(&report.score);
+        // A dead but widely used dependency is the riskiest
+        report.risk = report.popularity.as_ref().map(|popularity| {
+            let inactivity = match report.verdict {
+                Some(Verdict::Archived | Verdict::Disabled) => 1.0,
+                _ => (1.0 - report.score.total).clamp(0.0, 1.0),
+            };
+            self.importance(popularity) * inactivity
+        });
+    }
+
+    /// How widely used a repository is (0-1): stars or dependents on a log
+    /// scale, full at `important_stars` or `important_dependents`
+    pub fn importance(&self, popularity: &Popularity) -> f64 {
+        let scaled = |count: usize, full: f64| (count as f64).ln_1p() / full.ln_1p();
+        let dependents = popularity.dependents.map_or(0.0, |count| scaled(count, self.thresholds.important_dependents));
+        scaled(popularity.stars, self.thresholds.important_stars).max(dependents).min(1.0)
+    }
+
+    /// Scores the metrics of a report against the thresholds in `config` (defaults must be set)
+    pub fn score(&self, report: &RepositoryReport, config: &Config) -> ScoreBreakdown {
+        self.score_at(report, config, self.clock.now())
+    }
+
+    /// Like [`score`](Self::score), with day-based metrics relative to `now`
+    pub fn score_at(&self, report: &RepositoryReport, config: &Config, now: DateTime<Utc>) -> ScoreBreakdown {
+        let days_since = (now - report.last_commit.date_utc).num_days() as f64;
 
         // Recency: decreases linearly to 0 at 2 * max_days (smoother transition)
         let recency_scale = (config.get_max_days() as f64) * self.thresholds.recency_scale_multiplier;
         let recency_score = (1.0 - (days_since / recency_scale)).clamp(0.0, 1.0);
 
         // Other normalized scores
-        let commits_score = (commits as f64 / config.get_min_commits() as f64).clamp(0.0, 1.0);
-        let contributors_score = (contributors as f64 / config.get_min_contributors() as f64).clamp(0.0, 1.0);
-        let prs_score = (open_prs as f64 / config.get_prs_scale()).clamp(0.0, 1.0);
-        let issues_score = (open_issues as f64 / config.get_issues_scale()).clamp(0.0, 1.0);
+        let ratio = |count: Option<usize>, scale: f64| count.map(|c| (c as f64 / scale).clamp(0.0, 1.0));
+        let commits_score = ratio(report.commits_total, config.get_min_commits() as f64);
+        let contributors_score = ratio(report.contributors_total, config.get_min_contributors() as f64);
+        // Pull requests: how they are handled when known, the open count otherwise
+        let prs_score = match &report.pull_requests {
+            Some(pulls) => responsiveness_score(pulls, config),
+            None => ratio(report.open_pull_requests, config.get_prs_scale()),
+        };
+        // Issues: how they are triaged when known, the open count otherwise
+        let issues_score = match &report.issues {
+            Some(issues) => issue_health_score(issues),
+            None => ratio(report.open_issues, config.get_issues_scale()),
+        };
+        // Cadence: share of the expected active weeks, steady work beats bursts
+        let cadence_score = ratio(
+            report.cadence.as_ref().map(|cadence| cadence.active_weeks),
+            config.get_min_active_weeks() as f64,
+        );
+        // Bus factor: committers behind 80% of the last year's commits, one
+        // maintainer with many drive-by contributors scores low
+        let bus_factor_score = ratio(
+            report.bus_factor.as_ref().map(|bus_factor| bus_factor.committers_80),
+            config.get_min_contributors() as f64,
+        );
+        // CI: opt-in, as a red default branch says more about some projects than others
+        let ci_score = report.ci.as_ref().filter(|_| config.score_ci).and_then(|ci| ci_score(ci, config));
         
-        // Release scoring: recent release = high score, old release = lower score
-        let release_score = if let Some(release) = latest_release {
-            if let Some(published_at) = release.published_at {
-                let release_days_ago = (Utc::now() - published_at).num_days() as f64;
+        // Release scoring: the release history when there is one, otherwise
+        // recent release = high score, old release = lower score
+        let history = report
+            .releases
+            .as_ref()
+            .filter(|releases| releases.days_since_stable.or(releases.days_since_prerelease).is_some());
+        let release_score = if let Some(releases) = history {
+            release_cadence_score(releases, config)
+        } else if let Some(release) = &report.last_release {
+            if let Some(published_at) = release.date_utc {
+                let release_days_ago = (now - published_at).num_days() as f64;
                 let max_release_days = config.get_max_release_days() as f64;
                 
                 // Score decreases linearly with age, prerelease versions get penalty
                 let base_score = (1.0 - (release_days_ago / max_release_days)).clamp(0.0, 1.0);
-                if release.prerelease {
+                if release.is_prerelease {
                     base_score * 0.7  // 30% penalty for prereleases
                 } else {
                     base_score
@@ -95,21 +262,124 @@ impl ProjectScorer {
             0.0  // No releases = no score
         };
 
-        let weighted_score = recency_score * self.weights.recency
-            + commits_score * self.weights.commits
-            + contributors_score * self.weights.contributors
-            + prs_score * self.weights.prs
-            + issues_score * self.weights.issues
-            + release_score * self.weights.releases;
+        // A metric that failed to be fetched counts as 0: leaving it out like an
+        // unsupported one would let a rate limit raise the score
+        let mut failed = Vec::new();
+        let mut penalize = |name: &str, score: Option<f64>, fields: &[&str]| {
+            if score.is_none() && fields.iter().any(|field| report.errors.contains_key(*field)) {
+                failed.push(name.to_string());
+                return Some(0.0);
+            }
+            score
+        };
+        let commits_score = penalize("commits", commits_score, &["commits_total"]);
+        let contributors_score = penalize("contributors", contributors_score, &["contributors_total"]);
+        let prs_score = penalize("prs", prs_score, &["pull_requests", "open_pull_requests"]);
+        let issues_score = penalize("issues", issues_score, &["issues", "open_issues"]);
+        let release_known = report.releases.is_some() || !report.errors.contains_key("last_release");
+        penalize("releases", release_known.then_some(release_score), &["last_release"]);
+        let cadence_score = penalize("cadence", cadence_score, &["cadence"]);
+        let bus_factor_score = penalize("bus_factor", bus_factor_score, &["bus_factor"]);
+        let ci_score = if config.score_ci { penalize("ci", ci_score, &["ci"]) } else { None };
+
+        // Weighted average over the components the forge provides
+        let components = [
+            (Some(recency_score), self.weights.recency),
+            (commits_score, self.weights.commits),
+            (contributors_score, self.weights.contributors),
+            (prs_score, self.weights.prs),
+            (issues_score, self.weights.issues),
+            (Some(release_score), self.weights.releases),
+            (cadence_score, self.weights.cadence),
+            (bus_factor_score, self.weights.bus_factor),
+            (ci_score, self.weights.ci),
+        ];
+        let (weighted_sum, weight_sum) = components
+            .iter()
+            .filter_map(|(score, weight)| score.map(|s| (s * weight, *weight)))
+            .fold((0.0, 0.0), |(sum, weights), (s, w)| (sum + s, weights + w));
+        let weighted_score = if weight_sum > 0.0 { weighted_sum / weight_sum } else { 0.0 };
 
-        // Final rule: alive if weighted score >= threshold OR recency is strong (recent commit)
-        weighted_score >= self.thresholds.activity_threshold || recency_score >= self.thresholds.recency_threshold
+        ScoreBreakdown {
+            recency: recency_score,
+            commits: commits_score,
+            contributors: contributors_score,
+            prs: prs_score,
+            issues: issues_score,
+            releases: release_score,
+            cadence: cadence_score,
+            bus_factor: bus_factor_score,
+            ci: ci_score,
+            total: weighted_score,
+            failed,
+        }
+    }
+}
+
+/// Mean of the head state (pending counts half), the pass rate and the age of
+/// the last passed run against `max_days`; `None` without any CI results
+fn ci_score(ci: &CiHealth, config: &Config) -> Option<f64> {
+    let head = ci.head.map(|state| match state {
+        CiState::Success => 1.0,
+        CiState::Pending => 0.5,
+        CiState::Failure => 0.0,
+    });
+    let green = match ci.days_since_green {
+        Some(days) => Some((1.0 - days as f64 / config.get_max_days() as f64).clamp(0.0, 1.0)),
+        None => (ci.runs > 0).then_some(0.0),
+    };
+    let parts: Vec<f64> = [head, ci.pass_rate, green].into_iter().flatten().collect();
+    (!parts.is_empty()).then(|| parts.iter().sum::<f64>() / parts.len() as f64)
+}
+
+/// Mean of the age of the latest release (a prerelease with the 30% penalty),
+/// the stable releases of the last year against `min_releases` and their median
+/// interval against the one `min_releases` implies
+fn release_cadence_score(releases: &ReleaseCadence, config: &Config) -> f64 {
+    let age = |days: i64| (1.0 - days as f64 / config.get_max_release_days() as f64).clamp(0.0, 1.0);
+    let stable = releases.days_since_stable.map_or(0.0, age);
+    let prerelease = releases.days_since_prerelease.map_or(0.0, |days| age(days) * 0.7);
+    let min_releases = config.get_min_releases() as f64;
+    let frequency = (releases.releases_365d as f64 / min_releases).min(1.0);
+    let expected_interval = ACTIVITY_DAYS as f64 / min_releases;
+    let regularity = releases.median_interval_days.map(|days| (expected_interval / days.max(1.0)).min(1.0));
+    let parts: Vec<f64> = [Some(stable.max(prerelease)), Some(frequency), regularity].into_iter().flatten().collect();
+    parts.iter().sum::<f64>() / parts.len() as f64
+}
+
+/// Mean of the response time (full within a day, none at `stale_days`), the
+/// merged share and the stale open pull requests against `prs_scale`; `None`
+/// without pull requests to judge
+fn responsiveness_score(pulls: &PullRequestResponsiveness, config: &Config) -> Option<f64> {
+    if pulls.opened == 0 && pulls.stale_open == 0 {
+        return None;
     }
+    let stale_hours = (pulls.stale_days * 24) as f64;
+    let response = pulls
+        .median_first_response_hours
+        .map(|hours| (1.0 - (hours - 24.0).max(0.0) / (stale_hours - 24.0).max(1.0)).clamp(0.0, 1.0));
+    let stale = (1.0 - pulls.stale_open as f64 / config.get_prs_scale()).clamp(0.0, 1.0);
+    let parts: Vec<f64> = [response, pulls.merged_share, Some(stale)].into_iter().flatten().collect();
+    Some(parts.iter().sum::<f64>() / parts.len() as f64)
+}
+
+/// Mean of the response time (full within a day, none after 30 days), the
+/// closed to opened ratio of the last 90 days and the share of open issues
+/// that aren't untouched; `None` without issues to judge
+fn issue_health_score(issues: &IssueHealth) -> Option<f64> {
+    let response = issues
+        .median_first_response_hours
+        .map(|hours| (1.0 - (hours - 24.0).max(0.0) / (30.0 * 24.0 - 24.0)).clamp(0.0, 1.0));
+    let closing = (issues.opened_90d > 0).then(|| (issues.closed_90d as f64 / issues.opened_90d as f64).min(1.0));
+    let touched = issues.untouched_percent.map(|percent| 1.0 - percent / 100.0);
+    let parts: Vec<f64> = [response, closing, touched].into_iter().flatten().collect();
+    (!parts.is_empty()).then(|| parts.iter().sum::<f64>() / parts.len() as f64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::report::{BusFactor, Cadence, RepositoryMetadata, sample_report};
     use chrono::Utc;
 
     fn create_test_config() -> Config {
@@ -122,17 +392,38 @@ mod tests {
             prs_scale: Some(10.0),
             issues_scale: Some(20.0),
             max_release_days: Some(365),
+            min_active_weeks: Some(26),
+            min_releases: Some(4),
             ..Default::default()
         }
     }
 
+    fn create_test_report(
+        days_ago: i64,
+        commits: usize,
+        contributors: usize,
+        open_prs: usize,
+        open_issues: usize,
+    ) -> RepositoryReport {
+        let mut report = RepositoryReport {
+            commits_total: Some(commits),
+            contributors_total: Some(contributors),
+            open_pull_requests: Some(open_prs),
+            open_issues: Some(open_issues),
+            last_release: None,
+            ..sample_report()
+        };
+        report.last_commit.date_utc = Utc::now() - chrono::Duration::days(days_ago);
+        report
+    }
+
     #[test]
     fn test_recent_commit_is_alive() {
         let scorer = ProjectScorer::new();
         let config = create_test_config();
-        let recent_date = Utc::now() - chrono::Duration::days(1);
+        let report = create_test_report(1, 50, 1, 0, 0);
         
-        let result = scorer.is_project_alive(&recent_date, 50, 1, 0, 0, None, &config);
+        let result = scorer.is_alive(&scorer.score(&report, &config));
         assert!(result, "Recent commit should make project alive");
     }
 
@@ -140,9 +431,9 @@ mod tests {
     fn test_old_but_established_project_is_alive() {
         let scorer = ProjectScorer::new();
         let config = create_test_config();
-        let old_date = Utc::now() - chrono::Duration::days(100);
+        let report = create_test_report(100, 1000, 10, 5, 10);
         
-        let result = scorer.is_project_alive(&old_date, 1000, 10, 5, 10, None, &config);
+        let result = scorer.is_alive(&scorer.score(&report, &config));
         assert!(result, "Established project should be alive even with old commits");
     }
 
@@ -150,9 +441,9 @@ mod tests {
     fn test_old_and_small_project_is_dead() {
         let scorer = ProjectScorer::new();
         let config = create_test_config();
-        let old_date = Utc::now() - chrono::Duration::days(200);
+        let report = create_test_report(200, 10, 1, 0, 0);
         
-        let result = scorer.is_project_alive(&old_date, 10, 1, 0, 0, None, &config);
+        let result = scorer.is_alive(&scorer.score(&report, &config));
         assert!(!result, "Old and small project should be dead");
     }
 
@@ -160,10 +451,357 @@ mod tests {
     fn test_edge_case_exact_thresholds() {
         let scorer = ProjectScorer::new();
         let config = create_test_config();
-        let threshold_date = Utc::now() - chrono::Duration::days(60);
+        let report = create_test_report(60, 100, 3, 10, 20);
         
         // Exactly at thresholds
-        let result = scorer.is_project_alive(&threshold_date, 100, 3, 10, 20, None, &config);
+        let result = scorer.is_alive(&scorer.score(&report, &config));
         assert!(result, "Project at exact thresholds should be alive");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_score_breakdown_components() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let report = create_test_report(60, 50, 3, 5, 40);
+
+        let score = scorer.score(&report, &config);
+        assert!((score.recency - 0.5).abs() < 0.01);
+        assert_eq!(score.commits, Some(0.5));
+        assert_eq!(score.contributors, Some(1.0));
+        assert_eq!(score.prs, Some(0.5));
+        assert_eq!(score.issues, Some(1.0));
+        assert_eq!(score.releases, 0.0);
+        assert!(score.total > 0.0 && score.total < 1.0);
+    }
+
+    #[test]
+    fn test_unsupported_components_are_left_out() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let zeroed = create_test_report(10, 1000, 0, 10, 0);
+        let partial = RepositoryReport {
+            contributors_total: None,
+            open_issues: None,
+            ..zeroed.clone()
+        };
+
+        let zeroed_score = scorer.score(&zeroed, &config);
+        let partial_score = scorer.score(&partial, &config);
+        assert_eq!(partial_score.contributors, None);
+        assert_eq!(partial_score.issues, None);
+        // Missing metrics are not counted as zero
+        assert!(partial_score.total > zeroed_score.total);
+        let expected = (0.35 * partial_score.recency + 0.15 + 0.10) / 0.75;
+        assert!((partial_score.total - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cadence_component() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let report = create_test_report(10, 1000, 10, 10, 20);
+        assert_eq!(scorer.score(&report, &config).cadence, None);
+
+        // A burst of work in a few weeks scores below steady work
+        let burst = RepositoryReport {
+            cadence: Some(Cadence { commits_365d: 200, active_weeks: 4, ..Default::default() }),
+            ..report.clone()
+        };
+        let steady = RepositoryReport {
+            cadence: Some(Cadence { commits_365d: 52, active_weeks: 40, ..Default::default() }),
+            ..report
+        };
+        let burst_score = scorer.score(&burst, &config);
+        let steady_score = scorer.score(&steady, &config);
+        assert!((burst_score.cadence.unwrap() - 4.0 / 26.0).abs() < 1e-9);
+        assert_eq!(steady_score.cadence, Some(1.0));
+        assert!(steady_score.total > burst_score.total);
+    }
+
+    #[test]
+    fn test_bus_factor_component() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        // Many contributors overall, but one of them does nearly all the work
+        let report = RepositoryReport {
+            bus_factor: Some(BusFactor { committers_50: 1, committers_80: 1, active_365d: 40, ..Default::default() }),
+            ..create_test_report(10, 1000, 300, 10, 20)
+        };
+        let shared = RepositoryReport {
+            bus_factor: Some(BusFactor { committers_50: 2, committers_80: 4, active_365d: 12, ..Default::default() }),
+            ..report.clone()
+        };
+        let score = scorer.score(&report, &config);
+        assert!((score.bus_factor.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(scorer.score(&shared, &config).bus_factor, Some(1.0));
+        assert!(scorer.score(&shared, &config).total > score.total);
+    }
+
+    #[test]
+    fn test_pull_request_responsiveness_replaces_count() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let report = create_test_report(10, 1000, 10, 10, 20);
+        assert_eq!(scorer.score(&report, &config).prs, Some(1.0));
+
+        // Ten open pull requests, but they are answered and merged
+        let handled = PullRequestResponsiveness {
+            window_days: 90,
+            opened: 12,
+            median_first_response_hours: Some(12.0),
+            median_merge_hours: Some(72.0),
+            merged_share: Some(0.75),
+            stale_days: 30,
+            stale_open: 0,
+        };
+        let score = scorer.score(&RepositoryReport { pull_requests: Some(handled.clone()), ..report.clone() }, &config);
+        assert!((score.prs.unwrap() - (1.0 + 0.75 + 1.0) / 3.0).abs() < 1e-9);
+
+        // Ten of them ignored for over a month, the others answered after a week
+        let ignored = PullRequestResponsiveness {
+            median_first_response_hours: Some(24.0 * 7.0),
+            merged_share: None,
+            stale_open: 10,
+            ..handled
+        };
+        let score = scorer.score(&RepositoryReport { pull_requests: Some(ignored.clone()), ..report.clone() }, &config);
+        let response = 1.0 - 6.0 * 24.0 / (29.0 * 24.0);
+        assert!((score.prs.unwrap() - response / 2.0).abs() < 1e-9);
+
+        let quiet = PullRequestResponsiveness { opened: 0, stale_open: 0, ..ignored };
+        assert_eq!(scorer.score(&RepositoryReport { pull_requests: Some(quiet), ..report }, &config).prs, None);
+    }
+
+    #[test]
+    fn test_issue_health_replaces_count() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let report = create_test_report(10, 1000, 10, 5, 20);
+        assert_eq!(scorer.score(&report, &config).issues, Some(1.0));
+
+        // Twenty open issues, but new ones are answered and mostly closed
+        let triaged = IssueHealth {
+            median_first_response_hours: Some(6.0),
+            opened_90d: 10,
+            closed_90d: 8,
+            untouched_percent: Some(25.0),
+            labeled_percent: Some(90.0),
+        };
+        let score = scorer.score(&RepositoryReport { issues: Some(triaged.clone()), ..report.clone() }, &config);
+        assert!((score.issues.unwrap() - (1.0 + 0.8 + 0.75) / 3.0).abs() < 1e-9);
+
+        // Nothing opened lately and every open issue left alone
+        let abandoned = IssueHealth {
+            median_first_response_hours: None,
+            opened_90d: 0,
+            closed_90d: 0,
+            untouched_percent: Some(100.0),
+            ..triaged
+        };
+        let score = scorer.score(&RepositoryReport { issues: Some(abandoned.clone()), ..report.clone() }, &config);
+        assert_eq!(score.issues, Some(0.0));
+
+        let quiet = IssueHealth { untouched_percent: None, labeled_percent: None, ..abandoned };
+        assert_eq!(scorer.score(&RepositoryReport { issues: Some(quiet), ..report }, &config).issues, None);
+    }
+
+    #[test]
+    fn test_archived_is_dead() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let mut report = create_test_report(30, 1000, 10, 5, 10);
+        report.metadata = Some(RepositoryMetadata { archived: true, ..Default::default() });
+
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, Some(Verdict::Archived));
+        assert!(!report.project_alive);
+        // The score is still computed, only the verdict is overridden
+        assert!(scorer.is_alive(&report.score));
+
+        report.metadata = Some(RepositoryMetadata { archived: true, disabled: true, ..Default::default() });
+        assert_eq!(scorer.verdict(&report), Some(Verdict::Disabled));
+        report.metadata = Some(RepositoryMetadata { fork: true, ..Default::default() });
+        assert_eq!(scorer.verdict(&report), None);
+    }
+
+    #[test]
+    fn test_license_policy() {
+        use crate::report::License;
+
+        let scorer = ProjectScorer::new();
+        let license = |spdx_id: &str| Some(License { spdx_id: spdx_id.to_string(), name: None });
+        let mut report = create_test_report(30, 1000, 10, 5, 10);
+        report.license = license("GPL-3.0-only");
+
+        // No policy allows anything
+        let mut config = create_test_config();
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, None);
+
+        config.license_deny = Some(vec!["gpl-3.0-only".to_string(), License::NONE.to_string()]);
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, Some(Verdict::LicenseDenied));
+        assert!(!report.project_alive);
+
+        // Only listed licenses pass an allow list, the deny list still wins
+        config.license_allow = Some(vec!["MIT".to_string(), "GPL-3.0-only".to_string()]);
+        report.license = license("Apache-2.0");
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, Some(Verdict::LicenseDenied));
+        report.license = license("MIT");
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, None);
+        report.license = Some(License::none());
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, Some(Verdict::LicenseDenied));
+
+        // An archived repository stays archived, an unknown license fails the policy
+        report.metadata = Some(RepositoryMetadata { archived: true, ..Default::default() });
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, Some(Verdict::Archived));
+        report.metadata = None;
+        report.license = None;
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, Some(Verdict::LicenseDenied));
+
+        // Without a policy an unknown license isn't judged
+        scorer.evaluate(&mut report, &create_test_config());
+        assert_eq!(report.verdict, None);
+
+        // Nor is one the forge doesn't report
+        report.unsupported = vec!["license".to_string()];
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.verdict, None);
+    }
+
+    #[test]
+    fn test_risk_ranks_popular_inactive_repositories() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let popularity = |stars: usize, dependents: Option<usize>| Popularity { stars, dependents, ..Default::default() };
+        assert_eq!(scorer.importance(&popularity(0, None)), 0.0);
+        assert!((scorer.importance(&popularity(100, None)) - 0.5).abs() < 0.01);
+        assert_eq!(scorer.importance(&popularity(50_000, None)), 1.0);
+        // Dependents count when they say more than the stars
+        assert!((scorer.importance(&popularity(100, Some(1_000))) - 1.0).abs() < 1e-9);
+
+        let mut dead = create_test_report(400, 20, 1, 0, 0);
+        dead.popularity = Some(popularity(10_000, None));
+        scorer.evaluate(&mut dead, &config);
+        let mut alive = create_test_report(1, 1000, 10, 5, 10);
+        alive.popularity = Some(popularity(10_000, None));
+        scorer.evaluate(&mut alive, &config);
+        assert!(dead.risk.unwrap() > 0.5);
+        assert!(dead.risk.unwrap() > alive.risk.unwrap());
+
+        // Archived is fully inactive whatever the score, unknown popularity has no risk
+        alive.metadata = Some(RepositoryMetadata { archived: true, ..Default::default() });
+        scorer.evaluate(&mut alive, &config);
+        assert_eq!(alive.risk, Some(1.0));
+        alive.popularity = None;
+        scorer.evaluate(&mut alive, &config);
+        assert_eq!(alive.risk, None);
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let as_of = Utc::now() - chrono::Duration::days(365);
+        let scorer = ProjectScorer::new().with_clock(crate::clock::FixedClock(as_of));
+        let config = create_test_config();
+        let mut report = create_test_report(0, 10, 1, 0, 0);
+        report.last_commit.date_utc = as_of - chrono::Duration::days(30);
+
+        // 30 days old as of the clock's time, over a year old now
+        scorer.evaluate(&mut report, &config);
+        assert_eq!(report.checked_at, Some(as_of));
+        assert!((report.score.recency - 0.75).abs() < 0.01);
+        assert!(!ProjectScorer::new().is_alive(&ProjectScorer::new().score(&report, &config)));
+    }
+
+    #[test]
+    fn test_release_history_replaces_latest_release() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let report = create_test_report(10, 1000, 10, 5, 20);
+
+        // Quarterly releases, the last one a month ago
+        let quarterly = ReleaseCadence {
+            releases_365d: 4,
+            median_interval_days: Some(91.0),
+            days_since_stable: Some(30),
+            minor: 3,
+            patch: 1,
+            ..Default::default()
+        };
+        let score = scorer.score(&RepositoryReport { releases: Some(quarterly.clone()), ..report.clone() }, &config);
+        assert!((score.releases - (1.0 - 30.0 / 365.0 + 1.0 + 1.0) / 3.0).abs() < 1e-9);
+
+        // One old release and a recent release candidate
+        let stalled = ReleaseCadence {
+            releases_365d: 1,
+            median_interval_days: None,
+            days_since_stable: Some(300),
+            days_since_prerelease: Some(5),
+            ..quarterly
+        };
+        let score = scorer.score(&RepositoryReport { releases: Some(stalled), ..report.clone() }, &config);
+        assert!((score.releases - ((1.0 - 5.0 / 365.0) * 0.7 + 0.25) / 2.0).abs() < 1e-9);
+
+        // Without any release or tag the latest release (none here) decides
+        let none = RepositoryReport { releases: Some(ReleaseCadence::default()), ..report };
+        assert_eq!(scorer.score(&none, &config).releases, 0.0);
+    }
+
+    #[test]
+    fn test_ci_component_is_opt_in() {
+        let scorer = ProjectScorer::new();
+        let mut config = create_test_config();
+        let report = create_test_report(10, 1000, 10, 5, 20);
+        // Red for a while: the head and most recent runs fail, the last green run was 30 days ago
+        let red = RepositoryReport {
+            ci: Some(CiHealth {
+                head: Some(CiState::Failure),
+                runs: 20,
+                pass_rate: Some(0.25),
+                days_since_green: Some(30),
+            }),
+            ..report.clone()
+        };
+        let unscored = scorer.score(&red, &config);
+        assert_eq!(unscored.ci, None);
+        assert_eq!(unscored.total, scorer.score(&report, &config).total);
+
+        config.score_ci = true;
+        let scored = scorer.score(&red, &config);
+        assert!((scored.ci.unwrap() - (0.0 + 0.25 + 0.5) / 3.0).abs() < 1e-9);
+        assert!(scored.total < unscored.total);
+
+        // Neither statuses nor runs
+        let none = RepositoryReport { ci: Some(CiHealth::default()), ..report };
+        assert_eq!(scorer.score(&none, &config).ci, None);
+    }
+
+    #[test]
+    fn test_failed_metrics_count_as_zero() {
+        let scorer = ProjectScorer::new();
+        let config = create_test_config();
+        let report = create_test_report(10, 1000, 10, 10, 20);
+        let mut failed = report.clone();
+        failed.errors.insert("last_release".to_string(), "timeout".to_string());
+
+        // A failed release fetch scores like no release
+        let score = scorer.score(&failed, &config);
+        assert_eq!(score.total, scorer.score(&report, &config).total);
+        assert_eq!(score.failed, vec!["releases".to_string()]);
+        assert!(scorer.score(&report, &config).failed.is_empty());
+
+        // A failed metric lowers the score where an unsupported one is left out
+        let unsupported = RepositoryReport { contributors_total: None, ..report.clone() };
+        let mut rate_limited = unsupported.clone();
+        rate_limited.errors.insert("contributors_total".to_string(), "403 rate limit exceeded".to_string());
+        let score = scorer.score(&rate_limited, &config);
+        assert_eq!(score.contributors, Some(0.0));
+        assert_eq!(score.failed, vec!["contributors".to_string()]);
+        assert!(score.total < scorer.score(&unsupported, &config).total);
+    }
+}
@@ -1,5 +1,11 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::report::{
+    BusFactor, Cadence, CiHealth, CiState, CommunityProfile, IssueHealth, License, PullRequestResponsiveness, ReleaseCadence,
+    Popularity, ReleaseSource, RepositoryMetadata, SecurityPosture, SecurityState,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CommitInfo {
@@ -21,13 +27,13 @@ pub struct AuthorMeta {
 }
 
 #[derive(Deserialize)]
-pub struct SearchCommitsResp {
-    pub total_count: usize,
+pub(crate) struct SearchCommitsResp {
+    pub(crate) total_count: usize,
 }
 
 #[derive(Deserialize)]
-pub struct SearchIssuesResp {
-    pub total_count: usize,
+pub(crate) struct SearchIssuesResp {
+    pub(crate) total_count: usize,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -37,4 +43,1180 @@ pub struct ReleaseInfo {
     pub published_at: Option<DateTime<Utc>>,
     pub prerelease: bool,
     pub draft: bool,
-}
\ No newline at end of file
+}
+
+/// Repository itself, fetched once for the report and for the sections that
+/// need its default branch or permissions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryInfo {
+    pub metadata: RepositoryMetadata,
+    /// Stars, forks and watchers, without dependents
+    pub popularity: Popularity,
+    /// The credentials have admin access, `false` when unknown
+    pub admin: bool,
+}
+
+/// Repository object of the GitHub and Gitea REST APIs
+#[derive(Deserialize)]
+pub(crate) struct RepositoryResp {
+    pub(crate) full_name: String,
+    #[serde(default)]
+    pub(crate) archived: bool,
+    #[serde(default)]
+    pub(crate) disabled: bool,
+    #[serde(default)]
+    pub(crate) fork: bool,
+    pub(crate) parent: Option<ParentResp>,
+    /// `is_template` on GitHub, `template` on Gitea
+    #[serde(default, alias = "template")]
+    pub(crate) is_template: bool,
+    pub(crate) default_branch: Option<String>,
+    pub(crate) pushed_at: Option<DateTime<Utc>>,
+    /// Access of the authenticated user, absent without a token
+    pub(crate) permissions: Option<PermissionsResp>,
+    /// `stargazers_count` on GitHub, `stars_count` on Gitea
+    #[serde(default, alias = "stars_count")]
+    pub(crate) stargazers_count: usize,
+    #[serde(default)]
+    pub(crate) forks_count: usize,
+    /// Watchers on GitHub, where `watchers_count` counts the stars
+    pub(crate) subscribers_count: Option<usize>,
+    /// Watchers on Gitea
+    pub(crate) watchers_count: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PermissionsResp {
+    #[serde(default)]
+    pub(crate) admin: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ParentResp {
+    pub(crate) full_name: String,
+}
+
+impl RepositoryResp {
+    /// Metadata of the repository requested as `owner/repo`
+    pub(crate) fn metadata(self, owner: &str, repo: &str) -> RepositoryMetadata {
+        RepositoryMetadata {
+            archived: self.archived,
+            fork: self.fork,
+            disabled: self.disabled,
+            parent: self.parent.map(|parent| parent.full_name),
+            is_template: self.is_template,
+            default_branch: self.default_branch,
+            pushed_at: self.pushed_at,
+            moved_to: RepositoryMetadata::moved_to(owner, repo, &self.full_name),
+        }
+    }
+
+    /// Metadata, popularity and access of the repository requested as `owner/repo`
+    pub(crate) fn info(self, owner: &str, repo: &str) -> RepositoryInfo {
+        RepositoryInfo {
+            popularity: self.popularity(),
+            admin: self.permissions.as_ref().is_some_and(|permissions| permissions.admin),
+            metadata: self.metadata(owner, repo),
+        }
+    }
+
+    /// Stars, forks and watchers; dependents are fetched separately
+    pub(crate) fn popularity(&self) -> Popularity {
+        Popularity {
+            stars: self.stargazers_count,
+            forks: self.forks_count,
+            watchers: self.subscribers_count.or(self.watchers_count),
+            ..Default::default()
+        }
+    }
+}
+
+/// Days of commit history the forges fetch for [`CommitActivity`]
+pub const ACTIVITY_DAYS: i64 = 365;
+/// Pages of 100 commits the forges list at most for a [`CommitActivity`]
+pub const ACTIVITY_MAX_PAGES: usize = 10;
+
+/// Commits per day over the last [`ACTIVITY_DAYS`], the raw data of a [`Cadence`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitActivity {
+    days: BTreeMap<NaiveDate, usize>,
+}
+
+impl CommitActivity {
+    /// Records `commits` on `date`
+    pub fn add(&mut self, date: NaiveDate, commits: usize) {
+        if commits > 0 {
+            *self.days.entry(date).or_default() += commits;
+        }
+    }
+
+    /// Cadence over the windows ending at `now`; commits after `now` are ignored
+    pub fn cadence(&self, now: DateTime<Utc>) -> Cadence {
+        let today = now.date_naive();
+        let commits_within = |days: i64| -> usize {
+            self.days.range(today - Duration::days(days - 1)..=today).map(|(_, commits)| commits).sum()
+        };
+
+        let active_weeks: BTreeSet<i64> = self
+            .days
+            .range(today - Duration::days(52 * 7 - 1)..=today)
+            .map(|(day, _)| (today - *day).num_days() / 7)
+            .collect();
+
+        // Stretches between the window start, every day with commits and today
+        let start = today - Duration::days(ACTIVITY_DAYS);
+        let mut previous = start;
+        let mut longest_gap_days = 0;
+        for day in self.days.range(start + Duration::days(1)..=today).map(|(day, _)| *day).chain([today]) {
+            longest_gap_days = longest_gap_days.max((day - previous).num_days());
+            previous = day;
+        }
+
+        Cadence {
+            commits_30d: commits_within(30),
+            commits_90d: commits_within(90),
+            commits_365d: commits_within(ACTIVITY_DAYS),
+            active_weeks: active_weeks.len(),
+            longest_gap_days,
+        }
+    }
+}
+
+/// Committers whose share of the commits [`BusFactor::top_share`] reports
+pub const TOP_CONTRIBUTORS: usize = 3;
+
+/// Commits per committer and day over the last [`ACTIVITY_DAYS`], the raw data of a [`BusFactor`].
+/// Committers are identified by login or lowercased email, whatever the forge provides.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContributorActivity {
+    committers: BTreeMap<String, BTreeMap<NaiveDate, usize>>,
+}
+
+impl ContributorActivity {
+    /// Records `commits` by `committer` on `date`
+    pub fn add(&mut self, committer: &str, date: NaiveDate, commits: usize) {
+        if commits > 0 {
+            *self.committers.entry(committer.to_string()).or_default().entry(date).or_default() += commits;
+        }
+    }
+
+    /// Distribution over the windows ending at `now`; commits after `now` are ignored
+    pub fn bus_factor(&self, now: DateTime<Utc>) -> BusFactor {
+        let today = now.date_naive();
+        let commits_within = |days: &BTreeMap<NaiveDate, usize>, window: i64| -> usize {
+            days.range(today - Duration::days(window - 1)..=today).map(|(_, commits)| commits).sum()
+        };
+
+        let mut yearly: Vec<usize> = self
+            .committers
+            .values()
+            .map(|days| commits_within(days, ACTIVITY_DAYS))
+            .filter(|&commits| commits > 0)
+            .collect();
+        yearly.sort_unstable_by(|a, b| b.cmp(a));
+        let total: usize = yearly.iter().sum();
+
+        // Fewest committers whose commits add up to `share` of the total
+        let committers_for = |share: f64| -> usize {
+            let mut sum = 0;
+            yearly
+                .iter()
+                .take_while(|&&commits| {
+                    let short = (sum as f64) < share * total as f64;
+                    sum += commits;
+                    short
+                })
+                .count()
+        };
+
+        BusFactor {
+            top_share: if total > 0 {
+                yearly.iter().take(TOP_CONTRIBUTORS).sum::<usize>() as f64 / total as f64
+            } else {
+                0.0
+            },
+            committers_50: committers_for(0.5),
+            committers_80: committers_for(0.8),
+            active_90d: self.committers.values().filter(|days| commits_within(days, 90) > 0).count(),
+            active_365d: yearly.len(),
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, DateTime<Utc>)> for ContributorActivity {
+    fn from_iter<I: IntoIterator<Item = (S, DateTime<Utc>)>>(commits: I) -> Self {
+        let mut activity = ContributorActivity::default();
+        for (committer, date) in commits {
+            activity.add(committer.as_ref(), date.date_naive(), 1);
+        }
+        activity
+    }
+}
+
+/// Life cycle of a pull request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestInfo {
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub merged_at: Option<DateTime<Utc>>,
+    /// First comment or review by a maintainer, when the forge reports its time
+    pub first_response_at: Option<DateTime<Utc>>,
+    /// A maintainer responded, also known without the time of the response
+    pub responded: bool,
+}
+
+/// Pull requests opened in at least the last [`ACTIVITY_DAYS`] and all open ones
+/// (up to [`ACTIVITY_MAX_PAGES`]), by number; the raw data of a [`PullRequestResponsiveness`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PullRequestActivity {
+    pulls: BTreeMap<u64, PullRequestInfo>,
+}
+
+impl PullRequestActivity {
+    pub fn insert(&mut self, number: u64, pull: PullRequestInfo) {
+        self.pulls.insert(number, pull);
+    }
+
+    /// Records a maintainer response to pull request `number`, unknown numbers are ignored
+    pub fn respond(&mut self, number: u64, at: DateTime<Utc>) {
+        if let Some(pull) = self.pulls.get_mut(&number) {
+            pull.responded = true;
+            pull.first_response_at = Some(pull.first_response_at.map_or(at, |first| first.min(at)));
+        }
+    }
+
+    /// Responsiveness over the `window_days` ending at `now`; whatever happened
+    /// after `now` is ignored
+    pub fn responsiveness(&self, now: DateTime<Utc>, window_days: i64, stale_days: i64) -> PullRequestResponsiveness {
+        let before_now = |at: Option<DateTime<Utc>>| at.filter(|at| *at <= now);
+        let existing = || self.pulls.values().filter(|pull| pull.created_at <= now);
+        let opened: Vec<_> = existing().filter(|pull| now - pull.created_at <= Duration::days(window_days)).collect();
+
+        let hours = |from: DateTime<Utc>, to: DateTime<Utc>| (to - from).num_minutes() as f64 / 60.0;
+        let first_responses = opened
+            .iter()
+            .filter_map(|pull| before_now(pull.first_response_at).map(|at| hours(pull.created_at, at)))
+            .collect();
+        let merges = opened
+            .iter()
+            .filter_map(|pull| before_now(pull.merged_at).map(|at| hours(pull.created_at, at)))
+            .collect();
+        let closed = opened.iter().filter(|pull| before_now(pull.closed_at).is_some()).count();
+        let merged = opened.iter().filter(|pull| before_now(pull.merged_at).is_some()).count();
+
+        let responded = |pull: &PullRequestInfo| match pull.first_response_at {
+            Some(at) => at <= now,
+            None => pull.responded,
+        };
+        let stale_open = existing()
+            .filter(|pull| before_now(pull.closed_at).is_none())
+            .filter(|pull| now - pull.created_at > Duration::days(stale_days))
+            .filter(|pull| !responded(pull))
+            .count();
+
+        PullRequestResponsiveness {
+            window_days,
+            opened: opened.len(),
+            median_first_response_hours: median(first_responses),
+            median_merge_hours: median(merges),
+            merged_share: (closed > 0).then(|| merged as f64 / closed as f64),
+            stale_days,
+            stale_open,
+        }
+    }
+}
+
+/// Days [`IssueHealth`] counts opened and closed issues and response times over
+pub const ISSUE_WINDOW_DAYS: i64 = 90;
+/// Days without activity after which an open issue is untouched
+pub const UNTOUCHED_DAYS: i64 = 180;
+
+/// State of an issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueInfo {
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Last activity of any kind
+    pub updated_at: DateTime<Utc>,
+    pub labeled: bool,
+    /// First comment by a maintainer, when the forge reports its time
+    pub first_response_at: Option<DateTime<Utc>>,
+}
+
+/// Open issues and the ones closed in the last [`ACTIVITY_DAYS`] (up to
+/// [`ACTIVITY_MAX_PAGES`]), by number; the raw data of an [`IssueHealth`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IssueActivity {
+    issues: BTreeMap<u64, IssueInfo>,
+}
+
+impl IssueActivity {
+    pub fn insert(&mut self, number: u64, issue: IssueInfo) {
+        self.issues.insert(number, issue);
+    }
+
+    /// Records a maintainer response to issue `number`, unknown numbers are ignored
+    pub fn respond(&mut self, number: u64, at: DateTime<Utc>) {
+        if let Some(issue) = self.issues.get_mut(&number) {
+            issue.first_response_at = Some(issue.first_response_at.map_or(at, |first| first.min(at)));
+        }
+    }
+
+    /// Health as of `now`. The current labels and last activity are all that is
+    /// known, so they are used for earlier times too.
+    pub fn health(&self, now: DateTime<Utc>) -> IssueHealth {
+        let within_window = |at: DateTime<Utc>| at <= now && now - at <= Duration::days(ISSUE_WINDOW_DAYS);
+        let opened: Vec<_> = self.issues.values().filter(|issue| within_window(issue.created_at)).collect();
+        let closed_90d = self.issues.values().filter(|issue| issue.closed_at.is_some_and(within_window)).count();
+
+        let first_responses = opened
+            .iter()
+            .filter_map(|issue| {
+                let at = issue.first_response_at.filter(|at| *at <= now)?;
+                Some((at - issue.created_at).num_minutes() as f64 / 60.0)
+            })
+            .collect();
+
+        let open: Vec<_> = self
+            .issues
+            .values()
+            .filter(|issue| issue.created_at <= now && issue.closed_at.is_none_or(|at| at > now))
+            .collect();
+        let percent_of_open = |count: usize| (!open.is_empty()).then(|| count as f64 * 100.0 / open.len() as f64);
+        let untouched = open
+            .iter()
+            .filter(|issue| now - issue.updated_at > Duration::days(UNTOUCHED_DAYS))
+            .count();
+
+        IssueHealth {
+            median_first_response_hours: median(first_responses),
+            opened_90d: opened.len(),
+            closed_90d,
+            untouched_percent: percent_of_open(untouched),
+            labeled_percent: percent_of_open(open.iter().filter(|issue| issue.labeled).count()),
+        }
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((values[mid - 1] + values[mid]) / 2.0),
+        _ => Some(values[mid]),
+    }
+}
+
+/// Version tags whose dates the forges look up when falling back to tags
+pub const TAG_HISTORY_MAX: usize = 20;
+
+/// Semantic version of a tag such as `v1.2.3`, `release-1.2` or `pkg/v2.0.0-rc.1`;
+/// a missing patch number is 0. Orders prereleases before their release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// `false` for prereleases such as `-rc.1`
+    pub stable: bool,
+}
+
+/// Version component a release changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl Version {
+    /// Version of `tag`, `None` when it isn't one; a prefix must end in `v`, `-`, `_` or `/`
+    pub fn parse(tag: &str) -> Option<Version> {
+        let start = tag.find(|c: char| c.is_ascii_digit())?;
+        let prefix = &tag[..start];
+        if !(prefix.is_empty() || prefix.ends_with(['v', 'V', '-', '_', '/'])) {
+            return None;
+        }
+        let version = tag[start..].split('+').next()?;
+        let (core, stable) = match version.split_once('-') {
+            Some((core, _)) => (core, false),
+            None => (version, true),
+        };
+        let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+        let major = parts.next()??;
+        let minor = parts.next()??;
+        let patch = parts.next().unwrap_or(Some(0))?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Version { major, minor, patch, stable })
+    }
+
+    /// Component changed since `previous`, `None` unless this version is higher
+    pub fn bump_from(&self, previous: &Version) -> Option<Bump> {
+        if self <= previous {
+            None
+        } else if self.major != previous.major {
+            Some(Bump::Major)
+        } else if self.minor != previous.minor {
+            Some(Bump::Minor)
+        } else {
+            Some(Bump::Patch)
+        }
+    }
+}
+
+/// Release or version tag of a [`ReleaseHistory`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseEntry {
+    pub tag: String,
+    pub date: DateTime<Utc>,
+    pub prerelease: bool,
+}
+
+/// Releases published in the last [`ACTIVITY_DAYS`] and before (up to
+/// [`ACTIVITY_MAX_PAGES`]), or the newest version tags of a project without
+/// releases; the raw data of a [`ReleaseCadence`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseHistory {
+    source: ReleaseSource,
+    releases: Vec<ReleaseEntry>,
+}
+
+impl ReleaseHistory {
+    /// History of published releases; drafts and releases without a date are left out
+    pub fn from_releases(releases: impl IntoIterator<Item = ReleaseInfo>) -> Self {
+        let releases = releases
+            .into_iter()
+            .filter(|release| !release.draft)
+            .filter_map(|release| {
+                Some(ReleaseEntry { date: release.published_at?, prerelease: release.prerelease, tag: release.tag_name })
+            })
+            .collect();
+        ReleaseHistory { source: ReleaseSource::Releases, releases }
+    }
+
+    /// History of `(tag, date)` pairs; tags that aren't versions are left out
+    /// and prerelease versions are prereleases
+    pub fn from_tags<S: Into<String>>(tags: impl IntoIterator<Item = (S, DateTime<Utc>)>) -> Self {
+        let releases = tags
+            .into_iter()
+            .filter_map(|(tag, date)| {
+                let tag = tag.into();
+                let version = Version::parse(&tag)?;
+                Some(ReleaseEntry { tag, date, prerelease: !version.stable })
+            })
+            .collect();
+        ReleaseHistory { source: ReleaseSource::Tags, releases }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.releases.is_empty()
+    }
+
+    /// Cadence as of `now`; releases after `now` are ignored
+    pub fn cadence(&self, now: DateTime<Utc>) -> ReleaseCadence {
+        let mut releases: Vec<&ReleaseEntry> = self.releases.iter().filter(|release| release.date <= now).collect();
+        releases.sort_by_key(|release| release.date);
+        let (prereleases, stable): (Vec<&ReleaseEntry>, Vec<&ReleaseEntry>) =
+            releases.into_iter().partition(|release| release.prerelease);
+        let within_year = |release: &ReleaseEntry| now - release.date <= Duration::days(ACTIVITY_DAYS);
+        let days_since = |releases: &[&ReleaseEntry]| releases.last().map(|release| (now - release.date).num_days());
+
+        let intervals = stable
+            .windows(2)
+            .filter(|pair| within_year(pair[1]))
+            .map(|pair| (pair[1].date - pair[0].date).num_hours() as f64 / 24.0)
+            .collect();
+
+        let mut cadence = ReleaseCadence {
+            source: self.source,
+            releases_365d: stable.iter().filter(|release| within_year(release)).count(),
+            prereleases_365d: prereleases.iter().filter(|release| within_year(release)).count(),
+            median_interval_days: median(intervals),
+            days_since_stable: days_since(&stable),
+            days_since_prerelease: days_since(&prereleases),
+            ..Default::default()
+        };
+
+        // Each recent release against the highest lower version released before it,
+        // so backports count as patches of their own line
+        let versions: Vec<Option<Version>> = stable.iter().map(|release| Version::parse(&release.tag)).collect();
+        for index in (0..stable.len()).filter(|index| within_year(stable[*index])) {
+            let Some(version) = versions[index] else { continue };
+            let previous = versions[..index].iter().flatten().filter(|previous| **previous < version).max();
+            match previous.and_then(|previous| version.bump_from(previous)) {
+                Some(Bump::Major) => cadence.major += 1,
+                Some(Bump::Minor) => cadence.minor += 1,
+                Some(Bump::Patch) => cadence.patch += 1,
+                None => {}
+            }
+        }
+        cadence
+    }
+}
+
+/// Finished workflow runs [`CiActivity`] keeps at most
+pub const CI_RUNS_MAX: usize = 100;
+
+/// Finished CI run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CiRun {
+    pub finished_at: DateTime<Utc>,
+    pub passed: bool,
+}
+
+/// State of the default branch head and its latest [`CI_RUNS_MAX`] finished
+/// runs; the raw data of a [`CiHealth`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CiActivity {
+    head: Option<CiState>,
+    runs: Vec<CiRun>,
+}
+
+impl CiActivity {
+    pub fn new(head: Option<CiState>, runs: impl IntoIterator<Item = CiRun>) -> Self {
+        let mut runs: Vec<CiRun> = runs.into_iter().collect();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.finished_at));
+        runs.truncate(CI_RUNS_MAX);
+        CiActivity { head, runs }
+    }
+
+    /// Health over the latest `max_runs` runs finished by `now`; the head state
+    /// is the current one whatever `now` is
+    pub fn health(&self, now: DateTime<Utc>, max_runs: usize) -> CiHealth {
+        let runs: Vec<&CiRun> = self.runs.iter().filter(|run| run.finished_at <= now).take(max_runs).collect();
+        let passed = runs.iter().filter(|run| run.passed).count();
+        CiHealth {
+            head: self.head,
+            runs: runs.len(),
+            pass_rate: (!runs.is_empty()).then(|| passed as f64 / runs.len() as f64),
+            days_since_green: self
+                .runs
+                .iter()
+                .find(|run| run.passed && run.finished_at <= now)
+                .map(|run| (now - run.finished_at).num_days()),
+        }
+    }
+}
+
+/// Combined commit status of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct CombinedStatusResp {
+    /// `success`, `failure`, `error` or `pending`, also `pending` without statuses
+    pub(crate) state: String,
+    pub(crate) total_count: usize,
+}
+
+/// Check runs of a commit of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct CheckRunsResp {
+    pub(crate) check_runs: Vec<CheckRunResp>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CheckRunResp {
+    /// `queued`, `in_progress` or `completed`
+    pub(crate) status: String,
+    pub(crate) conclusion: Option<String>,
+}
+
+/// Whether a run or check with `conclusion` passed, `None` when it neither
+/// passed nor failed (cancelled, skipped, neutral...)
+fn passed(conclusion: &str) -> Option<bool> {
+    match conclusion {
+        "success" => Some(true),
+        "failure" | "timed_out" | "startup_failure" | "action_required" => Some(false),
+        _ => None,
+    }
+}
+
+impl CombinedStatusResp {
+    /// State of the commit with its check runs: failing if anything failed,
+    /// pending if anything is still running, `None` without statuses or checks
+    pub(crate) fn combine(&self, checks: &CheckRunsResp) -> Option<CiState> {
+        let mut states = Vec::new();
+        if self.total_count > 0 {
+            states.push(match self.state.as_str() {
+                "success" => CiState::Success,
+                "pending" => CiState::Pending,
+                _ => CiState::Failure,
+            });
+        }
+        for check in &checks.check_runs {
+            states.push(match (check.status.as_str(), check.conclusion.as_deref().and_then(passed)) {
+                ("completed", Some(false)) => CiState::Failure,
+                ("completed", _) => CiState::Success,
+                _ => CiState::Pending,
+            });
+        }
+        [CiState::Failure, CiState::Pending, CiState::Success].into_iter().find(|state| states.contains(state))
+    }
+}
+
+/// Workflow runs of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct WorkflowRunsResp {
+    pub(crate) workflow_runs: Vec<WorkflowRunResp>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WorkflowRunResp {
+    pub(crate) conclusion: Option<String>,
+    /// Last change of the run, its end once it finished
+    pub(crate) updated_at: DateTime<Utc>,
+}
+
+impl WorkflowRunResp {
+    /// The run if it passed or failed
+    pub(crate) fn run(&self) -> Option<CiRun> {
+        let passed = passed(self.conclusion.as_deref()?)?;
+        Some(CiRun { finished_at: self.updated_at, passed })
+    }
+}
+
+/// Published security advisory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdvisoryInfo {
+    pub created_at: DateTime<Utc>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Security policy, advisories and settings of a repository; the raw data of a [`SecurityPosture`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityData {
+    pub security_policy: SecurityState,
+    /// Published advisories, `None` when the credentials can't list them
+    pub advisories: Option<Vec<AdvisoryInfo>>,
+    pub vulnerability_alerts: SecurityState,
+    pub dependabot_updates: SecurityState,
+    pub branch_protection: SecurityState,
+}
+
+impl SecurityData {
+    /// Posture as of `now`; advisories published after `now` are ignored, the
+    /// policy and settings are the current ones
+    pub fn posture(&self, now: DateTime<Utc>) -> SecurityPosture {
+        let advisories: Option<Vec<&AdvisoryInfo>> = self.advisories.as_ref().map(|advisories| {
+            advisories.iter().filter(|advisory| advisory.published_at <= now).collect()
+        });
+        let days_to_fix = advisories
+            .iter()
+            .flatten()
+            .map(|advisory| (advisory.published_at - advisory.created_at).num_hours().max(0) as f64 / 24.0)
+            .collect();
+        SecurityPosture {
+            security_policy: self.security_policy,
+            advisories: advisories.as_ref().map(Vec::len),
+            advisories_365d: advisories.as_ref().map(|advisories| {
+                advisories
+                    .iter()
+                    .filter(|advisory| now - advisory.published_at <= Duration::days(ACTIVITY_DAYS))
+                    .count()
+            }),
+            median_days_to_fix: median(days_to_fix),
+            vulnerability_alerts: self.vulnerability_alerts,
+            dependabot_updates: self.dependabot_updates,
+            branch_protection: self.branch_protection,
+        }
+    }
+}
+
+/// Repository security advisory of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct AdvisoryResp {
+    pub(crate) created_at: DateTime<Utc>,
+    /// `None` for drafts
+    pub(crate) published_at: Option<DateTime<Utc>>,
+}
+
+impl AdvisoryResp {
+    /// The advisory if it is published
+    pub(crate) fn info(&self) -> Option<AdvisoryInfo> {
+        Some(AdvisoryInfo { created_at: self.created_at, published_at: self.published_at? })
+    }
+}
+
+/// Branch of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct BranchResp {
+    pub(crate) protected: bool,
+}
+
+/// Git tree of the GitHub REST API, one directory without recursion
+#[derive(Deserialize)]
+pub(crate) struct TreeResp {
+    pub(crate) tree: Vec<TreeEntryResp>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TreeEntryResp {
+    /// Name within the directory
+    pub(crate) path: String,
+    /// `blob` for files, `tree` for directories
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    pub(crate) sha: String,
+}
+
+/// Community profile of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct CommunityProfileResp {
+    pub(crate) health_percentage: u8,
+    pub(crate) files: CommunityFilesResp,
+}
+
+/// Community files, `null` when missing
+#[derive(Deserialize)]
+pub(crate) struct CommunityFilesResp {
+    #[serde(default)]
+    pub(crate) readme: Option<serde::de::IgnoredAny>,
+    #[serde(default)]
+    pub(crate) contributing: Option<serde::de::IgnoredAny>,
+    #[serde(default)]
+    pub(crate) code_of_conduct: Option<serde::de::IgnoredAny>,
+}
+
+impl CommunityProfileResp {
+    pub(crate) fn profile(&self) -> CommunityProfile {
+        CommunityProfile {
+            health_percentage: self.health_percentage,
+            readme: self.files.readme.is_some(),
+            contributing: self.files.contributing.is_some(),
+            code_of_conduct: self.files.code_of_conduct.is_some(),
+        }
+    }
+}
+
+/// License file of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct LicenseFileResp {
+    pub(crate) license: Option<LicenseResp>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LicenseResp {
+    pub(crate) name: Option<String>,
+    pub(crate) spdx_id: Option<String>,
+}
+
+impl LicenseFileResp {
+    /// The detected license; a file GitHub doesn't recognize is `NOASSERTION`
+    pub(crate) fn license(self) -> License {
+        let Some(license) = self.license else {
+            return License { spdx_id: License::NOASSERTION.to_string(), name: None };
+        };
+        License {
+            spdx_id: license.spdx_id.unwrap_or_else(|| License::NOASSERTION.to_string()),
+            name: license.name,
+        }
+    }
+}
+
+/// Tag of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct TagResp {
+    pub(crate) name: String,
+    pub(crate) commit: TagCommitResp,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TagCommitResp {
+    pub(crate) sha: String,
+}
+
+/// Pull request of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct PullResp {
+    pub(crate) number: u64,
+    pub(crate) user: Option<UserResp>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) closed_at: Option<DateTime<Utc>>,
+    pub(crate) merged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UserResp {
+    pub(crate) login: String,
+}
+
+/// Issue of the GitHub REST API, which lists pull requests as issues too
+#[derive(Deserialize)]
+pub(crate) struct IssueResp {
+    pub(crate) number: u64,
+    pub(crate) user: Option<UserResp>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) closed_at: Option<DateTime<Utc>>,
+    pub(crate) updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub(crate) labels: Vec<serde_json::Value>,
+    /// Present for pull requests
+    pub(crate) pull_request: Option<serde_json::Value>,
+}
+
+impl IssueResp {
+    pub(crate) fn info(&self) -> IssueInfo {
+        IssueInfo {
+            created_at: self.created_at,
+            closed_at: self.closed_at,
+            updated_at: self.updated_at,
+            labeled: !self.labels.is_empty(),
+            first_response_at: None,
+        }
+    }
+}
+
+/// Issue or review comment of the GitHub REST API
+#[derive(Deserialize)]
+pub(crate) struct CommentResp {
+    /// `.../issues/{number}` for issue and pull request comments
+    pub(crate) issue_url: Option<String>,
+    /// `.../pulls/{number}` for review comments
+    pub(crate) pull_request_url: Option<String>,
+    pub(crate) user: Option<UserResp>,
+    pub(crate) author_association: String,
+    pub(crate) created_at: DateTime<Utc>,
+}
+
+impl CommentResp {
+    /// Issue or pull request number the comment belongs to
+    pub(crate) fn number(&self) -> Option<u64> {
+        let url = self.issue_url.as_ref().or(self.pull_request_url.as_ref())?;
+        url.rsplit('/').next()?.parse().ok()
+    }
+
+    /// Written by someone with write access to the repository
+    pub(crate) fn by_maintainer(&self) -> bool {
+        matches!(self.author_association.as_str(), "OWNER" | "MEMBER" | "COLLABORATOR")
+    }
+}
+
+/// Contributor of the GitHub contributor statistics; `author` is null for
+/// commits not linked to an account, those are left out
+#[derive(Deserialize)]
+pub(crate) struct ContributorStatsResp {
+    pub(crate) author: Option<StatsAuthorResp>,
+    pub(crate) weeks: Vec<StatsWeekResp>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StatsAuthorResp {
+    pub(crate) login: String,
+}
+
+/// Week of the GitHub contributor statistics: start as Unix time and commits
+#[derive(Deserialize)]
+pub(crate) struct StatsWeekResp {
+    pub(crate) w: i64,
+    pub(crate) c: usize,
+}
+
+impl FromIterator<ContributorStatsResp> for ContributorActivity {
+    /// Weekly totals are recorded on the first day of the week
+    fn from_iter<I: IntoIterator<Item = ContributorStatsResp>>(contributors: I) -> Self {
+        let mut activity = ContributorActivity::default();
+        for contributor in contributors {
+            let Some(author) = contributor.author else { continue };
+            for week in contributor.weeks {
+                if let Some(start) = DateTime::from_timestamp(week.w, 0) {
+                    activity.add(&author.login, start.date_naive(), week.c);
+                }
+            }
+        }
+        activity
+    }
+}
+
+/// Week of the GitHub commit activity statistics, `days` starting on Sunday
+#[derive(Deserialize)]
+pub(crate) struct ActivityWeekResp {
+    pub(crate) week: i64,
+    pub(crate) days: [usize; 7],
+}
+
+impl FromIterator<ActivityWeekResp> for CommitActivity {
+    fn from_iter<I: IntoIterator<Item = ActivityWeekResp>>(weeks: I) -> Self {
+        let mut activity = CommitActivity::default();
+        for week in weeks {
+            let Some(start) = DateTime::from_timestamp(week.week, 0) else { continue };
+            for (day, commits) in (0..).zip(week.days) {
+                activity.add(start.date_naive() + Duration::days(day), commits);
+            }
+        }
+        activity
+    }
+}
+
+impl FromIterator<DateTime<Utc>> for CommitActivity {
+    fn from_iter<I: IntoIterator<Item = DateTime<Utc>>>(dates: I) -> Self {
+        let mut activity = CommitActivity::default();
+        for date in dates {
+            activity.add(date.date_naive(), 1);
+        }
+        activity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cadence() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let days_ago = |days: i64| now - Duration::days(days);
+        // Weekly work in the last two months, one fix in the autumn, a commit "after" now
+        let mut dates: Vec<_> = (0..9).map(|week| days_ago(week * 7 + 2)).collect();
+        dates.extend([days_ago(200), days_ago(200), now + Duration::days(3)]);
+        let activity: CommitActivity = dates.into_iter().collect();
+
+        let cadence = activity.cadence(now);
+        assert_eq!(cadence.commits_30d, 4);
+        assert_eq!(cadence.commits_90d, 9);
+        assert_eq!(cadence.commits_365d, 11);
+        assert_eq!(cadence.active_weeks, 10);
+        // From the window start to the commits 200 days ago
+        assert_eq!(cadence.longest_gap_days, 165);
+
+        // Statistics weeks start on Sunday, 2024-06-09 here
+        let weeks: CommitActivity = [ActivityWeekResp { week: 1717891200, days: [0, 3, 0, 0, 0, 1, 0] }].into_iter().collect();
+        let cadence = weeks.cadence(now);
+        assert_eq!((cadence.commits_30d, cadence.active_weeks, cadence.longest_gap_days), (4, 1, 360));
+
+        let idle = CommitActivity::default().cadence(now);
+        assert_eq!((idle.commits_365d, idle.active_weeks, idle.longest_gap_days), (0, 0, 365));
+    }
+
+    #[test]
+    fn test_pull_request_responsiveness() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let days_ago = |days: i64| now - Duration::days(days);
+        let pull = |created: i64| PullRequestInfo {
+            created_at: days_ago(created),
+            closed_at: None,
+            merged_at: None,
+            first_response_at: None,
+            responded: false,
+        };
+        let mut activity = PullRequestActivity::default();
+        // Merged after two days, answered after a day
+        activity.insert(1, PullRequestInfo { closed_at: Some(days_ago(8)), merged_at: Some(days_ago(8)), ..pull(10) });
+        activity.respond(1, days_ago(9));
+        activity.respond(1, days_ago(8));
+        // Closed unmerged after four days, answered after three
+        activity.insert(2, PullRequestInfo { closed_at: Some(days_ago(16)), ..pull(20) });
+        activity.respond(2, days_ago(17));
+        // Open and ignored for two months, open and answered, and one from before the window
+        activity.insert(3, pull(60));
+        activity.insert(4, PullRequestInfo { responded: true, ..pull(45) });
+        activity.insert(5, pull(200));
+        activity.respond(42, days_ago(1));
+
+        let responsiveness = activity.responsiveness(now, 90, 30);
+        assert_eq!(responsiveness.opened, 4);
+        assert_eq!(responsiveness.median_first_response_hours, Some(48.0));
+        assert_eq!(responsiveness.median_merge_hours, Some(48.0));
+        assert_eq!(responsiveness.merged_share, Some(0.5));
+        assert_eq!(responsiveness.stale_open, 2);
+
+        // 18 days earlier the first one wasn't opened yet and the second one was still open
+        let earlier = activity.responsiveness(days_ago(18), 90, 30);
+        assert_eq!((earlier.opened, earlier.merged_share, earlier.median_first_response_hours), (3, None, None));
+        assert_eq!(earlier.stale_open, 2);
+        assert_eq!(median(vec![]), None);
+    }
+
+    #[test]
+    fn test_issue_health() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let days_ago = |days: i64| now - Duration::days(days);
+        let issue = |created: i64, updated: i64, labeled: bool| IssueInfo {
+            created_at: days_ago(created),
+            closed_at: None,
+            updated_at: days_ago(updated),
+            labeled,
+            first_response_at: None,
+        };
+        let mut activity = IssueActivity::default();
+        // Answered after six hours and closed, answered after a day, never answered
+        activity.insert(1, IssueInfo { closed_at: Some(days_ago(5)), ..issue(10, 5, true) });
+        activity.respond(1, days_ago(10) + Duration::hours(6));
+        activity.insert(2, issue(30, 29, true));
+        activity.respond(2, days_ago(29));
+        activity.insert(3, issue(60, 60, false));
+        // An old labeled issue nobody looked at for a year, one closed recently after a long time
+        activity.insert(4, issue(400, 365, true));
+        activity.insert(5, IssueInfo { closed_at: Some(days_ago(20)), ..issue(500, 20, false) });
+
+        let health = activity.health(now);
+        assert_eq!(health.median_first_response_hours, Some(15.0));
+        assert_eq!((health.opened_90d, health.closed_90d), (3, 2));
+        assert_eq!(health.untouched_percent, Some(100.0 / 3.0));
+        assert_eq!(health.labeled_percent, Some(200.0 / 3.0));
+
+        let empty = IssueActivity::default().health(now);
+        assert_eq!((empty.opened_90d, empty.untouched_percent, empty.labeled_percent), (0, None, None));
+    }
+
+    #[test]
+    fn test_version() {
+        let version = |major, minor, patch, stable| Some(Version { major, minor, patch, stable });
+        assert_eq!(Version::parse("v1.2"), version(1, 2, 0, true));
+        assert_eq!(Version::parse("release-1.2.3"), version(1, 2, 3, true));
+        assert_eq!(Version::parse("pkg/v2.0.0-rc.1"), version(2, 0, 0, false));
+        assert_eq!(Version::parse("1.0.0+build.5"), version(1, 0, 0, true));
+        assert_eq!(Version::parse("python3.8"), None);
+        assert_eq!(Version::parse("v1"), None);
+        assert_eq!(Version::parse("1.2.3.4"), None);
+        assert!(Version::parse("1.0.0-rc.1") < Version::parse("1.0.0"));
+
+        let v1_1_1 = Version::parse("1.1.1").unwrap();
+        assert_eq!(Version::parse("2.0.0").unwrap().bump_from(&v1_1_1), Some(Bump::Major));
+        assert_eq!(Version::parse("1.2.0").unwrap().bump_from(&v1_1_1), Some(Bump::Minor));
+        assert_eq!(Version::parse("1.1.2").unwrap().bump_from(&v1_1_1), Some(Bump::Patch));
+        assert_eq!(Version::parse("1.0.9").unwrap().bump_from(&v1_1_1), None);
+    }
+
+    #[test]
+    fn test_release_cadence() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let release = |tag: &str, days: i64, prerelease: bool, draft: bool| ReleaseInfo {
+            tag_name: tag.to_string(),
+            name: None,
+            published_at: Some(now - Duration::days(days)),
+            prerelease,
+            draft,
+        };
+        // A 2.0 line with a backport to 1.1, a nightly build, a draft and a release "after" now
+        let history = ReleaseHistory::from_releases([
+            release("v3.0.0", -2, false, false),
+            release("nightly", 1, true, false),
+            release("v2.2.0", 5, false, true),
+            release("v2.1.0", 10, false, false),
+            release("v1.1.2", 90, false, false),
+            release("v2.0.0", 100, false, false),
+            release("v2.0.0-rc.1", 120, true, false),
+            release("v1.1.1", 200, false, false),
+            release("v1.1.0", 300, false, false),
+            release("v1.0.0", 400, false, false),
+        ]);
+
+        let cadence = history.cadence(now);
+        assert_eq!(cadence.source, ReleaseSource::Releases);
+        assert_eq!((cadence.releases_365d, cadence.prereleases_365d), (5, 2));
+        assert_eq!(cadence.median_interval_days, Some(100.0));
+        assert_eq!((cadence.days_since_stable, cadence.days_since_prerelease), (Some(10), Some(1)));
+        // 1.1.2 is a patch of 1.1.1, not a downgrade from 2.0.0
+        assert_eq!((cadence.major, cadence.minor, cadence.patch), (1, 2, 2));
+
+        let tags = ReleaseHistory::from_tags([
+            ("v1.3.0-beta.1", now - Duration::days(3)),
+            ("latest", now - Duration::days(3)),
+            ("v1.2.0", now - Duration::days(30)),
+        ]);
+        let cadence = tags.cadence(now);
+        assert_eq!(cadence.source, ReleaseSource::Tags);
+        assert_eq!((cadence.releases_365d, cadence.prereleases_365d), (1, 1));
+        assert_eq!((cadence.median_interval_days, cadence.days_since_stable), (None, Some(30)));
+
+        let none = ReleaseHistory::default().cadence(now);
+        assert_eq!((none.releases_365d, none.days_since_stable, none.days_since_prerelease), (0, None, None));
+    }
+
+    #[test]
+    fn test_ci_health() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let run = |days: i64, passed: bool| CiRun { finished_at: now - Duration::days(days), passed };
+        // Red for the last three runs, green before, one run "after" now
+        let activity = CiActivity::new(
+            Some(CiState::Failure),
+            [run(-1, true), run(1, false), run(2, false), run(10, true), run(3, false), run(20, true), run(30, true)],
+        );
+
+        let health = activity.health(now, 5);
+        assert_eq!(health.head, Some(CiState::Failure));
+        assert_eq!(health.runs, 5);
+        assert_eq!(health.pass_rate, Some(2.0 / 5.0));
+        assert_eq!(health.days_since_green, Some(10));
+
+        let none = CiActivity::default().health(now, 20);
+        assert_eq!((none.head, none.runs, none.pass_rate, none.days_since_green), (None, 0, None, None));
+    }
+
+    #[test]
+    fn test_combined_ci_state() {
+        let status = |state: &str, total_count| CombinedStatusResp { state: state.to_string(), total_count };
+        let checks = |runs: &[(&str, Option<&str>)]| CheckRunsResp {
+            check_runs: runs
+                .iter()
+                .map(|(status, conclusion)| CheckRunResp {
+                    status: status.to_string(),
+                    conclusion: conclusion.map(str::to_string),
+                })
+                .collect(),
+        };
+        // GitHub reports `pending` for a commit without statuses
+        assert_eq!(status("pending", 0).combine(&checks(&[])), None);
+        assert_eq!(status("success", 1).combine(&checks(&[])), Some(CiState::Success));
+        let skipped = checks(&[("completed", Some("success")), ("completed", Some("skipped"))]);
+        assert_eq!(status("pending", 0).combine(&skipped), Some(CiState::Success));
+        let running = checks(&[("completed", Some("success")), ("in_progress", None)]);
+        assert_eq!(status("success", 2).combine(&running), Some(CiState::Pending));
+        let failed = checks(&[("completed", Some("timed_out")), ("queued", None)]);
+        assert_eq!(status("success", 2).combine(&failed), Some(CiState::Failure));
+        assert_eq!(status("error", 1).combine(&checks(&[])), Some(CiState::Failure));
+    }
+
+    #[test]
+    fn test_security_posture() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let advisory = |created: i64, published: i64| AdvisoryInfo {
+            created_at: now - Duration::days(created),
+            published_at: now - Duration::days(published),
+        };
+        // Fixed in 2, 10 and 30 days, one of them over a year ago, and one published "after" now
+        let data = SecurityData {
+            security_policy: SecurityState::Enabled,
+            advisories: Some(vec![advisory(12, 10), advisory(100, 90), advisory(430, 400), advisory(5, -1)]),
+            vulnerability_alerts: SecurityState::Unknown,
+            dependabot_updates: SecurityState::Enabled,
+            branch_protection: SecurityState::Disabled,
+        };
+
+        let posture = data.posture(now);
+        assert_eq!((posture.advisories, posture.advisories_365d), (Some(3), Some(2)));
+        assert_eq!(posture.median_days_to_fix, Some(10.0));
+        assert_eq!(posture.vulnerability_alerts, SecurityState::Unknown);
+        assert_eq!(posture.security_policy, SecurityState::Enabled);
+
+        let none = SecurityData { advisories: Some(Vec::new()), ..SecurityData::default() }.posture(now);
+        assert_eq!((none.advisories, none.median_days_to_fix), (Some(0), None));
+        assert_eq!(none.branch_protection, SecurityState::Unknown);
+        // Advisories the credentials can't list are unknown, not zero
+        let hidden = SecurityData::default().posture(now);
+        assert_eq!((hidden.advisories, hidden.advisories_365d), (None, None));
+        assert_eq!(hidden.security_policy, SecurityState::Unknown);
+    }
+
+    #[test]
+    fn test_bus_factor() {
+        let now = "2024-06-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let days_ago = |days: i64| now - Duration::days(days);
+        // One maintainer, a regular and three drive-by contributors, one of them long gone
+        let mut commits: Vec<_> = (0..60).map(|day| ("maintainer", days_ago(day * 5))).collect();
+        commits.extend((0..20).map(|day| ("regular", days_ago(day * 10 + 100))));
+        commits.extend([("drive-by-1", days_ago(3)), ("drive-by-2", days_ago(300)), ("gone", days_ago(400))]);
+        let activity: ContributorActivity = commits.into_iter().collect();
+
+        let bus_factor = activity.bus_factor(now);
+        assert!((bus_factor.top_share - 81.0 / 82.0).abs() < 1e-9);
+        assert_eq!(bus_factor.committers_50, 1);
+        assert_eq!(bus_factor.committers_80, 2);
+        assert_eq!(bus_factor.active_90d, 2);
+        assert_eq!(bus_factor.active_365d, 4);
+
+        let idle = ContributorActivity::default().bus_factor(now);
+        assert_eq!((idle.top_share, idle.committers_50, idle.active_365d), (0.0, 0, 0));
+    }
+}
@@ -0,0 +1,262 @@
+//! Checks against GitHub responses stored in `tests/cassettes`, one
+//! directory per scenario. Nothing goes over the network.
+//!
+//! The cassettes are synthetic: they were written by hand for made-up
+//! `fixtures/*` repositories, not recorded from GitHub. Each file holds one
+//! exchange in the format `--record` writes, but with a short readable name;
+//! a real recording adds a `-<hash>` suffix. Replay matches on the request,
+//! not the file name, so both kinds work side by side. Edit the JSON to
+//! change a scenario, and keep it consistent with what GitHub would return.
+
+use github_activity_check::{
+    Cassette, CiState, Config, FixedClock, ForgeClient, GitHubClient, ProjectScorer, ReleaseSource, RepositoryReport,
+    SecurityState, Verdict, check_repository_with,
+};
+use std::path::PathBuf;
+use std::process::Command;
+
+fn cassette_dir(scenario: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes").join(scenario)
+}
+
+async fn replay(scenario: &str, repo: &str) -> anyhow::Result<RepositoryReport> {
+    replay_with(scenario, repo, &Config::default()).await
+}
+
+async fn replay_with(scenario: &str, repo: &str, config: &Config) -> anyhow::Result<RepositoryReport> {
+    let client = GitHubClient::builder().cassette(Cassette::replay(cassette_dir(scenario))?).build()?;
+    // Scored as of a fixed date so verdicts don't change as the cassettes age
+    let scorer = ProjectScorer::new().with_clock(FixedClock("2024-06-15T00:00:00Z".parse()?));
+    check_repository_with(&client, &scorer, "fixtures", repo, config).await
+}
+
+#[tokio::test]
+async fn test_repository_without_releases() {
+    let config = Config { ci: true, triage: true, dependents: true, ..Config::default() };
+    let report = replay_with("no-releases", "no-releases", &config).await.unwrap();
+    assert_eq!(report.commits_total, Some(250));
+    assert_eq!(report.contributors_total, Some(4));
+    assert_eq!(report.open_pull_requests, Some(0));
+    assert_eq!(report.open_issues, Some(3));
+    assert!(report.last_release.is_none());
+    assert!(report.errors.is_empty());
+    assert_eq!(report.last_commit.author_name, "Jane Doe");
+    assert_eq!(report.last_commit.date_utc.to_rfc3339(), "2024-05-01T12:00:00+00:00");
+    assert!(report.project_alive);
+    assert_eq!(report.score.releases, 0.0);
+    // A commit every other week until the last one, six weeks before the check
+    let cadence = report.cadence.unwrap();
+    assert_eq!((cadence.commits_30d, cadence.commits_90d, cadence.commits_365d), (0, 8, 46));
+    assert_eq!(cadence.active_weeks, 23);
+    assert_eq!(cadence.longest_gap_days, 45);
+    // Jane wrote half of the last year's commits, Alice stopped three months ago
+    let bus_factor = report.bus_factor.unwrap();
+    assert_eq!((bus_factor.committers_50, bus_factor.committers_80), (1, 2));
+    assert_eq!((bus_factor.active_90d, bus_factor.active_365d), (3, 4));
+    assert!((bus_factor.top_share - 45.0 / 46.0).abs() < 1e-9);
+    // Jane's comment on her own pull request and a drive-by comment don't count as responses
+    let pulls = report.pull_requests.unwrap();
+    assert_eq!(pulls.opened, 3);
+    assert_eq!(pulls.median_first_response_hours, Some(25.5));
+    assert_eq!(pulls.median_merge_hours, Some(36.0));
+    assert!((pulls.merged_share.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(pulls.stale_open, 0);
+    // Pull requests in the issue listing are left out; #2 hasn't been touched since September
+    let issues = report.issues.unwrap();
+    assert_eq!(issues.median_first_response_hours, Some(9.0));
+    assert_eq!((issues.opened_90d, issues.closed_90d), (2, 2));
+    assert!((issues.untouched_percent.unwrap() - 100.0 / 3.0).abs() < 1e-9);
+    assert!((issues.labeled_percent.unwrap() - 200.0 / 3.0).abs() < 1e-9);
+    // Neither releases nor tags
+    let releases = report.releases.unwrap();
+    assert_eq!((releases.releases_365d, releases.days_since_stable), (0, None));
+    // A failing check on the head, two of five finished runs passed (the cancelled one doesn't count)
+    let ci = report.ci.unwrap();
+    assert_eq!(ci.head, Some(CiState::Failure));
+    assert_eq!((ci.runs, ci.pass_rate, ci.days_since_green), (5, Some(0.4), Some(75)));
+    assert_eq!(report.score.ci, None);
+    // No policy or Dependabot configuration, and alerts are hidden without admin access
+    let security = report.security.unwrap();
+    assert_eq!(security.security_policy, SecurityState::Disabled);
+    assert_eq!((security.advisories, security.median_days_to_fix), (Some(0), None));
+    assert_eq!(security.vulnerability_alerts, SecurityState::Unknown);
+    assert_eq!(security.dependabot_updates, SecurityState::Disabled);
+    assert_eq!(security.branch_protection, SecurityState::Disabled);
+    // Only a README, and no license
+    let community = report.community.unwrap();
+    assert_eq!(community.health_percentage, 28);
+    assert!(community.readme && !community.contributing && !community.code_of_conduct);
+    assert_eq!(report.license.unwrap().spdx_id, "NONE");
+    // Nobody depends on it, a few dozen stars keep it from ranking high
+    let popularity = report.popularity.unwrap();
+    assert_eq!((popularity.stars, popularity.forks, popularity.watchers), (42, 3, Some(2)));
+    assert_eq!(popularity.dependents, Some(0));
+    assert!(report.risk.unwrap() < 0.2);
+}
+
+#[tokio::test]
+async fn test_license_policy() {
+    let deny_unlicensed = Config { license_deny: Some(vec!["NONE".to_string()]), ..Config::default() };
+    let report = replay_with("no-releases", "no-releases", &deny_unlicensed).await.unwrap();
+    assert_eq!(report.verdict, Some(Verdict::LicenseDenied));
+    assert!(!report.project_alive);
+
+    let permissive = Config { license_allow: Some(vec!["MIT".to_string(), "Apache-2.0".to_string()]), ..Config::default() };
+    let report = replay_with("huge", "huge", &permissive).await.unwrap();
+    assert_eq!(report.license.unwrap().name.as_deref(), Some("MIT License"));
+    assert_eq!(report.verdict, None);
+    assert!(report.project_alive);
+    // The CI results and the dependents page are only fetched with `--ci` and `--dependents`
+    assert_eq!(report.ci, None);
+    assert_eq!(report.popularity.unwrap().dependents, None);
+    assert!(!report.unsupported.contains(&"dependents".to_string()));
+    // GitHub doesn't recognize the old tool's license
+    let report = replay_with("archived", "old-tool", &permissive).await.unwrap();
+    assert_eq!(report.license.unwrap().spdx_id, "NOASSERTION");
+    assert_eq!(report.verdict, Some(Verdict::Archived));
+}
+
+#[tokio::test]
+async fn test_release_history_from_tags() {
+    let client = GitHubClient::builder().cassette(Cassette::replay(cassette_dir("tags-only")).unwrap()).build().unwrap();
+    let now = "2024-06-15T00:00:00Z".parse().unwrap();
+    let history = client.get_release_history("fixtures", "tags-only", now).await.unwrap();
+    // `nightly` isn't a version, the others are dated by their commits
+    let releases = history.cadence(now);
+    assert_eq!(releases.source, ReleaseSource::Tags);
+    assert_eq!((releases.releases_365d, releases.prereleases_365d), (2, 1));
+    assert_eq!(releases.median_interval_days, Some(91.0));
+    assert_eq!((releases.days_since_stable, releases.days_since_prerelease), (Some(65), Some(13)));
+    assert_eq!((releases.major, releases.minor, releases.patch), (0, 1, 0));
+}
+
+#[tokio::test]
+async fn test_empty_repository() {
+    let err = replay("empty", "empty").await.unwrap_err();
+    assert!(format!("{err:#}").contains("409"));
+
+    // The statistics of an empty repository have no content
+    let client = GitHubClient::builder().cassette(Cassette::replay(cassette_dir("empty")).unwrap()).build().unwrap();
+    let now = "2024-06-15T00:00:00Z".parse().unwrap();
+    let (activity, committers) = client.get_commit_history("fixtures", "empty", now).await;
+    assert_eq!(activity.unwrap(), Default::default());
+    assert_eq!(committers.unwrap(), Default::default());
+}
+
+#[tokio::test]
+async fn test_huge_repository() {
+    let config = Config { ci: true, triage: true, dependents: true, ..Config::default() };
+    let report = replay_with("huge", "huge", &config).await.unwrap();
+    assert_eq!(report.commits_total, Some(312_345));
+    assert_eq!(report.contributors_total, Some(5000));
+    assert_eq!(report.open_pull_requests, Some(800));
+    assert_eq!(report.open_issues, Some(9876));
+    assert_eq!(report.last_release.unwrap().tag_name, "v2.0.0");
+    assert!(report.project_alive);
+    assert!(report.score.total > 0.95);
+    let metadata = report.metadata.unwrap();
+    assert_eq!(metadata.parent.as_deref(), Some("upstream/huge"));
+    assert_eq!(metadata.default_branch.as_deref(), Some("master"));
+    let pulls = report.pull_requests.unwrap();
+    assert_eq!((pulls.opened, pulls.stale_open), (2, 1));
+    assert_eq!(pulls.median_first_response_hours, Some(1.5));
+    let issues = report.issues.unwrap();
+    assert_eq!((issues.opened_90d, issues.closed_90d), (2, 2));
+    assert_eq!(issues.labeled_percent, Some(100.0));
+    // A 2.0 after three 1.x releases, and a release candidate since
+    let releases = report.releases.unwrap();
+    assert_eq!(releases.source, ReleaseSource::Releases);
+    assert_eq!((releases.releases_365d, releases.prereleases_365d), (5, 1));
+    assert_eq!(releases.median_interval_days, Some(47.0));
+    assert_eq!((releases.days_since_stable, releases.days_since_prerelease), (Some(14), Some(5)));
+    assert_eq!((releases.major, releases.minor, releases.patch), (1, 2, 2));
+    let ci = report.ci.unwrap();
+    assert_eq!(ci.head, Some(CiState::Success));
+    assert_eq!((ci.runs, ci.pass_rate, ci.days_since_green), (4, Some(0.75), Some(5)));
+    // Fixed in 2, 10 and 30 days, the last one over a year ago
+    let security = report.security.unwrap();
+    assert_eq!(security.security_policy, SecurityState::Enabled);
+    assert_eq!((security.advisories, security.advisories_365d), (Some(3), Some(2)));
+    assert_eq!(security.median_days_to_fix, Some(10.0));
+    assert_eq!(security.vulnerability_alerts, SecurityState::Enabled);
+    assert_eq!(security.dependabot_updates, SecurityState::Enabled);
+    assert_eq!(security.branch_protection, SecurityState::Enabled);
+    let community = report.community.unwrap();
+    assert_eq!(community.health_percentage, 100);
+    assert!(community.readme && community.contributing && community.code_of_conduct);
+    // Very popular, but healthy enough to be a low risk
+    let popularity = report.popularity.unwrap();
+    assert_eq!((popularity.stars, popularity.forks, popularity.watchers), (48_213, 5120, Some(1130)));
+    assert_eq!(popularity.dependents, Some(23_456));
+    assert!(report.risk.unwrap() < 0.05);
+}
+
+#[tokio::test]
+async fn test_archived_and_renamed_repository() {
+    let config = Config { ci: true, dependents: true, ..Config::default() };
+    let report = replay_with("archived", "old-tool", &config).await.unwrap();
+    // A commit a month ago and a fresh release would make it alive, but it is archived
+    assert!(ProjectScorer::new().is_alive(&report.score));
+    assert_eq!(report.verdict, Some(Verdict::Archived));
+    assert!(!report.project_alive);
+    assert_eq!(report.metadata.unwrap().moved_to.as_deref(), Some("fixtures/tool"));
+    // Pull requests and issues are only listed with `--triage`
+    assert!(report.pull_requests.is_none() && report.issues.is_none());
+    assert!(report.errors.is_empty());
+    // No statuses, checks or workflow runs
+    assert_eq!(report.ci, Some(Default::default()));
+    // The policy in the root and a `.yaml` configuration count, the branch and advisories are forbidden
+    let security = report.security.unwrap();
+    assert_eq!(security.security_policy, SecurityState::Enabled);
+    assert_eq!((security.advisories, security.median_days_to_fix), (None, None));
+    assert_eq!(security.dependabot_updates, SecurityState::Enabled);
+    assert_eq!(security.branch_protection, SecurityState::Unknown);
+    // Archived with over a thousand dependents is as risky as it gets
+    let popularity = report.popularity.unwrap();
+    assert_eq!((popularity.stars, popularity.dependents), (870, Some(1204)));
+    assert_eq!(report.risk, Some(1.0));
+}
+
+#[tokio::test]
+async fn test_repository_not_found() {
+    let err = replay("not-found", "missing").await.unwrap_err();
+    let message = format!("{err:#}");
+    assert!(message.contains("fixtures/missing"));
+    assert!(message.contains("404"));
+}
+
+#[tokio::test]
+async fn test_unrecorded_request_fails() {
+    let err = replay("huge", "other").await.unwrap_err();
+    assert!(format!("{err:#}").contains("No recorded response for GET"));
+}
+
+#[test]
+fn test_cli_replay() {
+    let output = Command::new(env!("CARGO_BIN_EXE_github-activity-check"))
+        .args(["--replay", cassette_dir("huge").to_str().unwrap(), "--format", "field:commits_total"])
+        .args(["fixtures", "huge"])
+        .env_remove("GITHUB_TOKEN")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "312345");
+}
+
+#[test]
+fn test_cli_as_of() {
+    let recency = |as_of: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_github-activity-check"))
+            .args(["--replay", cassette_dir("no-releases").to_str().unwrap(), "--as-of", as_of])
+            .args(["--format", "field:score.recency", "fixtures/no-releases"])
+            .env_remove("GITHUB_TOKEN")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    // The last commit is from 2024-05-01T12:00:00Z, recency reaches 0 after 120 days
+    assert_eq!(recency("2024-05-01T12:00:00Z"), "1.0");
+    assert_eq!(recency("2024-06-30T12:00:00Z"), "0.5");
+    assert_eq!(recency("2025-01-01"), "0.0");
+}